@@ -0,0 +1,9 @@
+#[path = "../headless_cli.rs"]
+mod headless_cli;
+
+use std::process;
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    process::exit(headless_cli::main("gol-headless", &args));
+}
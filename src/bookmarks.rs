@@ -0,0 +1,169 @@
+use rust_sdl_gol::camera::ViewState;
+use sdl3::event::Event;
+use sdl3::keyboard::{Keycode, Mod};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+/// How long the camera takes to fly to a bookmark.
+const TRANSITION_TIME: Duration = Duration::from_millis(600);
+
+/// A saved camera position: the cell at the centre of the window and the zoom.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct View {
+    pub x: f32,
+    pub y: f32,
+    pub zoom: f32,
+}
+
+impl View {
+    pub fn of(viewstate: &ViewState, output_size: (u32, u32)) -> View {
+        let center = viewstate.center(output_size);
+        View {
+            x: center.x,
+            y: center.y,
+            zoom: viewstate.zoom,
+        }
+    }
+}
+
+/// The bookmark name a number key saves to and recalls.
+pub fn numbered(keycode: Keycode) -> Option<&'static str> {
+    const NAMES: [&str; 10] = ["0", "1", "2", "3", "4", "5", "6", "7", "8", "9"];
+    let keys = [
+        Keycode::_0,
+        Keycode::_1,
+        Keycode::_2,
+        Keycode::_3,
+        Keycode::_4,
+        Keycode::_5,
+        Keycode::_6,
+        Keycode::_7,
+        Keycode::_8,
+        Keycode::_9,
+    ];
+    let index = keys.iter().position(|key| *key == keycode)?;
+    Some(NAMES[index])
+}
+
+/// An animated move of the camera from one view to another.
+#[derive(Debug, Clone, Copy)]
+pub struct Transition {
+    from: View,
+    to: View,
+    started: Instant,
+}
+
+impl Transition {
+    pub fn new(from: View, to: View) -> Self {
+        Transition {
+            from,
+            to,
+            started: Instant::now(),
+        }
+    }
+
+    /// Places the camera along the way. Returns false once it has arrived.
+    pub fn apply(&self, viewstate: &mut ViewState, output_size: (u32, u32)) -> bool {
+        let t = (self.started.elapsed().as_secs_f32() / TRANSITION_TIME.as_secs_f32()).min(1.0);
+        let eased = t * t * (3.0 - 2.0 * t);
+        // Zoom geometrically so each doubling takes the same time.
+        viewstate.zoom = self.from.zoom * (self.to.zoom / self.from.zoom).powf(eased);
+        viewstate.center_on(
+            self.from.x + (self.to.x - self.from.x) * eased,
+            self.from.y + (self.to.y - self.from.y) * eased,
+            output_size,
+        );
+        t < 1.0
+    }
+}
+
+pub enum BookmarkAction {
+    None,
+    Close,
+    Jump(View),
+    /// Save the current view under this name, replacing any bookmark already there.
+    Save(String),
+    Delete(String),
+}
+
+/// List of named bookmarks, filtered by typing. Enter jumps to the selected bookmark or saves
+/// the current view under the typed name if none match, and Ctrl+Enter always saves.
+pub struct BookmarkDialog {
+    pub open: bool,
+    pub input: String,
+    pub selected: usize,
+}
+
+impl BookmarkDialog {
+    pub fn new() -> BookmarkDialog {
+        BookmarkDialog {
+            open: false,
+            input: String::new(),
+            selected: 0,
+        }
+    }
+
+    /// Names of the bookmarks containing the typed text, ignoring case.
+    pub fn matches<'b>(&self, bookmarks: &'b BTreeMap<String, View>) -> Vec<&'b str> {
+        let query = self.input.trim().to_lowercase();
+        bookmarks
+            .keys()
+            .filter(|name| name.to_lowercase().contains(&query))
+            .map(String::as_str)
+            .collect()
+    }
+
+    pub fn handle_event(
+        &mut self,
+        event: &Event,
+        bookmarks: &BTreeMap<String, View>,
+    ) -> BookmarkAction {
+        match event {
+            Event::TextInput { text, .. } => {
+                self.input.push_str(text);
+                self.selected = 0;
+            }
+            Event::KeyDown {
+                keycode: Some(keycode),
+                keymod,
+                ..
+            } => match *keycode {
+                Keycode::Escape => return BookmarkAction::Close,
+                Keycode::Backspace => {
+                    self.input.pop();
+                    self.selected = 0;
+                }
+                Keycode::Up => self.selected = self.selected.saturating_sub(1),
+                Keycode::Down => {
+                    let count = self.matches(bookmarks).len();
+                    if self.selected + 1 < count {
+                        self.selected += 1;
+                    }
+                }
+                Keycode::Delete => {
+                    if let Some(name) = self.matches(bookmarks).get(self.selected) {
+                        let name = name.to_string();
+                        self.selected = self.selected.saturating_sub(1);
+                        return BookmarkAction::Delete(name);
+                    }
+                }
+                Keycode::Return => {
+                    let name = self.input.trim();
+                    let save = keymod.intersects(Mod::LCTRLMOD | Mod::RCTRLMOD);
+                    let selected = self.matches(bookmarks).get(self.selected).copied();
+                    match selected {
+                        Some(selected) if !save => {
+                            return BookmarkAction::Jump(bookmarks[selected]);
+                        }
+                        _ if !name.is_empty() => return BookmarkAction::Save(name.to_string()),
+                        _ => {}
+                    }
+                }
+                _ => {}
+            },
+            _ => {}
+        }
+        BookmarkAction::None
+    }
+}
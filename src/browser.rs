@@ -0,0 +1,72 @@
+use rust_sdl_gol::gol::Grid;
+use rust_sdl_gol::patterns::{load_library, Pattern};
+use sdl3::event::Event;
+use sdl3::keyboard::Keycode;
+
+pub enum BrowserAction {
+    None,
+    Close,
+    Pick(Grid),
+}
+
+pub struct PatternBrowser {
+    pub open: bool,
+    pub query: String,
+    pub selected: usize,
+    pub patterns: Vec<Pattern>,
+}
+
+impl PatternBrowser {
+    pub fn new() -> PatternBrowser {
+        PatternBrowser {
+            open: false,
+            query: String::new(),
+            selected: 0,
+            patterns: load_library(),
+        }
+    }
+
+    /// Indices into `patterns` of the entries matching the current query.
+    pub fn matches(&self) -> Vec<usize> {
+        self.patterns
+            .iter()
+            .enumerate()
+            .filter(|(_, pattern)| pattern.matches(&self.query))
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    pub fn handle_event(&mut self, event: &Event) -> BrowserAction {
+        match event {
+            Event::TextInput { text, .. } => {
+                self.query.push_str(text);
+                self.selected = 0;
+            }
+            Event::KeyDown {
+                keycode: Some(keycode),
+                ..
+            } => match *keycode {
+                Keycode::Escape => return BrowserAction::Close,
+                Keycode::Backspace => {
+                    self.query.pop();
+                    self.selected = 0;
+                }
+                Keycode::Up => self.selected = self.selected.saturating_sub(1),
+                Keycode::Down => {
+                    let count = self.matches().len();
+                    if self.selected + 1 < count {
+                        self.selected += 1;
+                    }
+                }
+                Keycode::Return => {
+                    if let Some(&index) = self.matches().get(self.selected) {
+                        return BrowserAction::Pick(self.patterns[index].grid.clone());
+                    }
+                }
+                _ => {}
+            },
+            _ => {}
+        }
+        BrowserAction::None
+    }
+}
@@ -0,0 +1,265 @@
+use rust_sdl_gol::gol::{GOL, Vec2Isize};
+use std::collections::HashSet;
+
+/// Largest brush, in cells across.
+pub const MAX_BRUSH_SIZE: u32 = 32;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BrushShape {
+    #[default]
+    Square,
+    Circle,
+}
+
+impl BrushShape {
+    pub const ALL: [BrushShape; 2] = [BrushShape::Square, BrushShape::Circle];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            BrushShape::Square => "square",
+            BrushShape::Circle => "circle",
+        }
+    }
+
+    pub fn named(name: &str) -> Option<BrushShape> {
+        BrushShape::ALL
+            .into_iter()
+            .find(|shape| shape.name() == name)
+    }
+
+    pub fn next(self) -> BrushShape {
+        let index = BrushShape::ALL
+            .iter()
+            .position(|shape| *shape == self)
+            .unwrap();
+        BrushShape::ALL[(index + 1) % BrushShape::ALL.len()]
+    }
+}
+
+/// What a stroke does to the cells it covers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stroke {
+    Draw,
+    Erase,
+    /// Flips each covered cell once per stroke, however often the stroke passes over it.
+    Invert,
+}
+
+/// Cells on the straight line from `from` to `to`, both included, by Bresenham's algorithm.
+pub fn line(from: Vec2Isize, to: Vec2Isize) -> Vec<Vec2Isize> {
+    let (dx, dy) = ((to.x - from.x).abs(), -(to.y - from.y).abs());
+    let (step_x, step_y) = ((to.x - from.x).signum(), (to.y - from.y).signum());
+    let mut cells = Vec::with_capacity(dx.max(-dy) as usize + 1);
+    let mut cell = from;
+    let mut error = dx + dy;
+    loop {
+        cells.push(cell);
+        if cell == to {
+            return cells;
+        }
+        let doubled = 2 * error;
+        if doubled >= dy {
+            error += dy;
+            cell.x += step_x;
+        }
+        if doubled <= dx {
+            error += dx;
+            cell.y += step_y;
+        }
+    }
+}
+
+/// Paints cells under the mouse, joining the positions of successive frames with lines so
+/// fast strokes don't leave gaps.
+pub struct Brush {
+    pub shape: BrushShape,
+    /// Cells across, from 1 to `MAX_BRUSH_SIZE`.
+    pub size: u32,
+    /// Whether the left button inverts cells instead of drawing them.
+    pub invert: bool,
+    /// Where the stroke in progress last painted.
+    last: Option<Vec2Isize>,
+    /// Cells the stroke in progress has already inverted.
+    inverted: HashSet<Vec2Isize>,
+}
+
+impl Brush {
+    pub fn new(shape: BrushShape, size: u32) -> Self {
+        Brush {
+            shape,
+            size: size.clamp(1, MAX_BRUSH_SIZE),
+            invert: false,
+            last: None,
+            inverted: HashSet::new(),
+        }
+    }
+
+    pub fn grow(&mut self) {
+        self.size = (self.size + 1).min(MAX_BRUSH_SIZE);
+    }
+
+    pub fn shrink(&mut self) {
+        self.size = self.size.saturating_sub(1).max(1);
+    }
+
+    /// Offsets of the cells the brush covers around the cell it's centred on. Even sizes extend
+    /// one cell further right and down.
+    pub fn footprint(&self) -> Vec<Vec2Isize> {
+        let size = self.size as isize;
+        let start = -(size - 1) / 2;
+        let center = (size - 1) as f32 / 2.0;
+        // A little under half the size, so small circles don't come out as squares.
+        let radius = self.size as f32 / 2.0 - 0.25;
+        (0..size)
+            .flat_map(|y| (0..size).map(move |x| (x, y)))
+            .filter(|&(x, y)| match self.shape {
+                BrushShape::Square => true,
+                BrushShape::Circle => {
+                    let (dx, dy) = (x as f32 - center, y as f32 - center);
+                    dx * dx + dy * dy <= radius * radius
+                }
+            })
+            .map(|(x, y)| Vec2Isize::new(start + x, start + y))
+            .collect()
+    }
+
+    /// Continues the stroke to `cell`, painting everything between it and the previous position.
+    pub fn stroke_to(&mut self, gol: &mut GOL, cell: Vec2Isize, stroke: Stroke) {
+        let from = self.last.unwrap_or(cell);
+        self.last = Some(cell);
+        let footprint = self.footprint();
+        for center in line(from, cell) {
+            for offset in &footprint {
+                let (x, y) = (center.x + offset.x, center.y + offset.y);
+                match stroke {
+                    Stroke::Draw => gol.set_cell(x, y, true),
+                    Stroke::Erase => gol.set_cell(x, y, false),
+                    Stroke::Invert => {
+                        if self.inverted.insert(Vec2Isize::new(x, y)) {
+                            gol.set_cell(x, y, !gol.grid.get_cell(x, y));
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn end_stroke(&mut self) {
+        self.last = None;
+        self.inverted.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_sdl_gol::gol::Grid;
+
+    fn cells(cells: &[(isize, isize)]) -> Vec<Vec2Isize> {
+        cells.iter().map(|&(x, y)| Vec2Isize::new(x, y)).collect()
+    }
+
+    #[test]
+    fn lines_include_both_ends() {
+        let (from, to) = (Vec2Isize::new(2, 3), Vec2Isize::new(7, 5));
+        let line = line(from, to);
+        assert_eq!(line.first(), Some(&from));
+        assert_eq!(line.last(), Some(&to));
+        assert_eq!(line.len(), 6);
+        assert_eq!(super::line(from, from), vec![from]);
+    }
+
+    #[test]
+    fn steep_lines_step_once_per_row() {
+        let line = line(Vec2Isize::new(0, 0), Vec2Isize::new(2, 6));
+        assert_eq!(line.len(), 7);
+        for (row, cell) in line.iter().enumerate() {
+            assert_eq!(cell.y, row as isize);
+        }
+        assert!(line.windows(2).all(|pair| pair[1].x - pair[0].x <= 1));
+    }
+
+    #[test]
+    fn lines_run_in_negative_directions() {
+        assert_eq!(
+            line(Vec2Isize::new(0, 0), Vec2Isize::new(-3, -3)),
+            cells(&[(0, 0), (-1, -1), (-2, -2), (-3, -3)])
+        );
+        assert_eq!(
+            line(Vec2Isize::new(1, -2), Vec2Isize::new(-2, -2)),
+            cells(&[(1, -2), (0, -2), (-1, -2), (-2, -2)])
+        );
+        let backwards = line(Vec2Isize::new(-1, 4), Vec2Isize::new(-3, -2));
+        assert_eq!(backwards.len(), 7);
+        assert_eq!(backwards.last(), Some(&Vec2Isize::new(-3, -2)));
+    }
+
+    #[test]
+    fn size_one_brushes_cover_one_cell() {
+        for shape in BrushShape::ALL {
+            assert_eq!(Brush::new(shape, 1).footprint(), cells(&[(0, 0)]));
+        }
+    }
+
+    #[test]
+    fn even_squares_extend_right_and_down() {
+        let footprint = Brush::new(BrushShape::Square, 2).footprint();
+        assert_eq!(footprint, cells(&[(0, 0), (1, 0), (0, 1), (1, 1)]));
+    }
+
+    #[test]
+    fn circles_are_symmetric_and_smaller_than_squares() {
+        for size in 2..=MAX_BRUSH_SIZE {
+            let footprint: HashSet<Vec2Isize> = Brush::new(BrushShape::Circle, size)
+                .footprint()
+                .into_iter()
+                .collect();
+            // Mirrored about the footprint's centre, which is off by half a cell for even sizes.
+            let far = (size as isize - 1) - 2 * ((size as isize - 1) / 2);
+            for cell in &footprint {
+                for image in [
+                    Vec2Isize::new(far - cell.x, cell.y),
+                    Vec2Isize::new(cell.x, far - cell.y),
+                    Vec2Isize::new(cell.y, cell.x),
+                ] {
+                    assert!(footprint.contains(&image), "size {}: {:?}", size, image);
+                }
+            }
+            if size >= 3 {
+                assert!(footprint.len() < (size * size) as usize, "size {}", size);
+            }
+        }
+    }
+
+    #[test]
+    fn inverting_strokes_flip_each_cell_once() {
+        let mut gol = GOL::new(Grid::new());
+        gol.set_cell(2, 0, true);
+        let mut brush = Brush::new(BrushShape::Square, 1);
+        // Out along the row and back over it, crossing every cell twice.
+        for x in [0, 4, 0] {
+            brush.stroke_to(&mut gol, Vec2Isize::new(x, 0), Stroke::Invert);
+        }
+        brush.end_stroke();
+        let expected: Grid = cells(&[(0, 0), (1, 0), (3, 0), (4, 0)])
+            .into_iter()
+            .collect();
+        assert_eq!(*gol.grid, expected);
+
+        // A new stroke can flip them back.
+        brush.stroke_to(&mut gol, Vec2Isize::new(0, 0), Stroke::Invert);
+        assert!(!gol.grid.get_cell(0, 0));
+    }
+
+    #[test]
+    fn strokes_join_distant_positions() {
+        let mut gol = GOL::new(Grid::new());
+        let mut brush = Brush::new(BrushShape::Square, 1);
+        brush.stroke_to(&mut gol, Vec2Isize::new(0, 0), Stroke::Draw);
+        brush.stroke_to(&mut gol, Vec2Isize::new(5, 5), Stroke::Draw);
+        assert_eq!(gol.grid.population(), 6);
+        brush.end_stroke();
+        brush.stroke_to(&mut gol, Vec2Isize::new(9, 0), Stroke::Erase);
+        assert_eq!(gol.grid.population(), 6);
+    }
+}
@@ -0,0 +1,127 @@
+//! The viewer's camera and the mapping between screen pixels and cells.
+//!
+//! Screen and cell coordinates share their orientation: `x` grows to the right and `y` grows
+//! down, so a cell's `x` is its column and its `y` its row. Cell `(x, y)` covers the screen
+//! square from `cell_to_screen((x, y))` to `cell_to_screen((x + 1, y + 1))`.
+
+use crate::gol::Vec2Isize;
+
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub struct Vector2 {
+    pub x: f32,
+    pub y: f32,
+}
+
+impl Vector2 {
+    pub fn new(x: f32, y: f32) -> Self {
+        Vector2 { x, y }
+    }
+}
+
+pub const MIN_ZOOM: f32 = 0.0001;
+pub const MAX_ZOOM: f32 = 10000.0;
+
+/// Share of the window a fitted pattern fills.
+const FIT_MARGIN: f32 = 0.9;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ViewState {
+    /// Screen position of the origin cell's top-left corner.
+    pub camera_pos: Vector2,
+    pub mouse_pos: Vector2,
+    /// Screen pixels per cell.
+    pub zoom: f32,
+}
+
+impl ViewState {
+    /// The point in cells, fractions included, under the screen point `point`.
+    pub fn screen_to_world(&self, point: Vector2) -> Vector2 {
+        // In f64 so far-off cells keep their precision at extreme zoom.
+        let axis = |p: f32, camera: f32| ((p as f64 - camera as f64) / self.zoom as f64) as f32;
+        Vector2::new(
+            axis(point.x, self.camera_pos.x),
+            axis(point.y, self.camera_pos.y),
+        )
+    }
+
+    /// The screen point over `point`, in cells.
+    pub fn world_to_screen(&self, point: Vector2) -> Vector2 {
+        let axis = |p: f32, camera: f32| (p as f64 * self.zoom as f64 + camera as f64) as f32;
+        Vector2::new(
+            axis(point.x, self.camera_pos.x),
+            axis(point.y, self.camera_pos.y),
+        )
+    }
+
+    /// The cell under the screen point `point`.
+    pub fn screen_to_cell(&self, point: Vector2) -> Vec2Isize {
+        let axis =
+            |p: f32, camera: f32| ((p as f64 - camera as f64) / self.zoom as f64).floor() as isize;
+        Vec2Isize::new(
+            axis(point.x, self.camera_pos.x),
+            axis(point.y, self.camera_pos.y),
+        )
+    }
+
+    /// Screen position of the top-left corner of `cell`.
+    pub fn cell_to_screen(&self, cell: Vec2Isize) -> Vector2 {
+        let axis = |c: isize, camera: f32| (c as f64 * self.zoom as f64 + camera as f64) as f32;
+        Vector2::new(
+            axis(cell.x, self.camera_pos.x),
+            axis(cell.y, self.camera_pos.y),
+        )
+    }
+
+    /// The cell under the mouse.
+    pub fn cursor_cell(&self) -> Vec2Isize {
+        self.screen_to_cell(self.mouse_pos)
+    }
+
+    /// Inclusive `(min, max)` corners of the cells that overlap a window of `output_size`.
+    pub fn visible_cells(&self, output_size: (u32, u32)) -> (Vec2Isize, Vec2Isize) {
+        let min = self.screen_to_cell(Vector2::new(0.0, 0.0));
+        let far = self.screen_to_world(Vector2::new(output_size.0 as f32, output_size.1 as f32));
+        let max = Vec2Isize::new(far.x.ceil() as isize - 1, far.y.ceil() as isize - 1);
+        (min, max)
+    }
+
+    /// The point at the centre of a window of `output_size`, in cells.
+    pub fn center(&self, output_size: (u32, u32)) -> Vector2 {
+        self.screen_to_world(Vector2::new(
+            output_size.0 as f32 / 2.0,
+            output_size.1 as f32 / 2.0,
+        ))
+    }
+
+    /// Moves the camera so the point `(x, y)`, in cells, is at the centre of the window.
+    pub fn center_on(&mut self, x: f32, y: f32, output_size: (u32, u32)) {
+        self.camera_pos = Vector2::new(
+            output_size.0 as f32 / 2.0 - x * self.zoom,
+            output_size.1 as f32 / 2.0 - y * self.zoom,
+        );
+    }
+
+    /// Zooms and centres the view so the inclusive `(min, max)` cell corners fit in the window
+    /// with a small margin.
+    pub fn fit(&mut self, (min, max): (Vec2Isize, Vec2Isize), output_size: (u32, u32)) {
+        let cols = (max.x - min.x + 1) as f32;
+        let rows = (max.y - min.y + 1) as f32;
+        let zoom = (output_size.0 as f32 / cols).min(output_size.1 as f32 / rows) * FIT_MARGIN;
+        self.zoom = zoom.clamp(MIN_ZOOM, MAX_ZOOM);
+        self.center_on(
+            (min.x + max.x + 1) as f32 / 2.0,
+            (min.y + max.y + 1) as f32 / 2.0,
+            output_size,
+        );
+    }
+}
+
+impl Default for ViewState {
+    fn default() -> Self {
+        ViewState {
+            camera_pos: Default::default(),
+            mouse_pos: Default::default(),
+            zoom: 4.0,
+        }
+    }
+}
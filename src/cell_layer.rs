@@ -0,0 +1,224 @@
+use crate::coloring::CellPaint;
+use crate::density::{DensityPyramid, MAX_LEVEL};
+use log::warn;
+use rust_sdl_gol::camera::{Vector2, ViewState};
+use rust_sdl_gol::gol::{Grid, Vec2Isize};
+use sdl3::pixels::{Color, PixelFormat, PixelFormatEnum};
+use sdl3::rect::Rect;
+use sdl3::render::{BlendMode, Canvas, FRect, ScaleMode, Texture, TextureCreator};
+use sdl3::video::{Window, WindowContext};
+
+const BYTES_PER_PIXEL: usize = 4;
+
+/// Packed format whose bytes sit in memory as `[r, g, b, a]`, the order texels are written in.
+#[cfg(target_endian = "little")]
+const TEXEL_FORMAT: PixelFormatEnum = PixelFormatEnum::ABGR8888;
+#[cfg(target_endian = "big")]
+const TEXEL_FORMAT: PixelFormatEnum = PixelFormatEnum::RGBA8888;
+
+/// Draws live cells by writing them into a streaming texture and blitting it in one copy.
+///
+/// Zoomed in, each texel is one cell and the texture is stretched over the visible cells.
+/// Zoomed out, each texel is one screen pixel, shaded by the fraction of the cells under it that
+/// are alive. Colour modes only apply zoomed in, as a pixel covering many cells has no single
+/// age or heat; the HUD says so.
+pub struct CellLayer<'a> {
+    texture_creator: &'a TextureCreator<WindowContext>,
+    texture: Option<Texture<'a>>,
+    /// Size of `texture`, which only grows so panning and zooming don't reallocate it.
+    capacity: (u32, u32),
+    /// Live cells counted per screen pixel when zoomed out, reused between frames.
+    coverage: Vec<f32>,
+}
+
+/// The part of the texture a frame uses and where it goes on screen.
+struct Layout {
+    /// Texels written this frame, starting at the texture's top-left corner.
+    size: (u32, u32),
+    /// Cell under texel `(0, 0)` when texels are cells.
+    origin: Vec2Isize,
+    dst: FRect,
+}
+
+impl<'a> CellLayer<'a> {
+    pub fn new(texture_creator: &'a TextureCreator<WindowContext>) -> Self {
+        CellLayer {
+            texture_creator,
+            texture: None,
+            capacity: (0, 0),
+            coverage: Vec::new(),
+        }
+    }
+
+    pub fn draw(
+        &mut self,
+        canvas: &mut Canvas<Window>,
+        grid: &Grid,
+        pyramid: &mut DensityPyramid,
+        viewstate: ViewState,
+        paint: &CellPaint,
+    ) {
+        let (width, height) = canvas.output_size().unwrap();
+        let Some(layout) = layout(width, height, viewstate) else {
+            return;
+        };
+        if viewstate.zoom < 1.0 {
+            self.accumulate_coverage(grid, pyramid, width, height, viewstate);
+        }
+        if !self.reserve(layout.size) {
+            return;
+        }
+        let Some(texture) = self.texture.as_mut() else {
+            return;
+        };
+
+        let (cols, rows) = layout.size;
+        let (r, g, b, a) = paint.theme.cell.rgba();
+        let lock_rect = Rect::new(0, 0, cols, rows);
+
+        let written = if viewstate.zoom >= 1.0 {
+            let (min, max) = viewstate.visible_cells((width, height));
+            texture.with_lock(lock_rect, |pixels, pitch| {
+                for row in pixels.chunks_mut(pitch).take(rows as usize) {
+                    row[..cols as usize * BYTES_PER_PIXEL].fill(0);
+                }
+                let mut put = |cell: Vec2Isize, color: Color| {
+                    let texel_x = (cell.x - layout.origin.x) as usize;
+                    let texel_y = (cell.y - layout.origin.y) as usize;
+                    let offset = texel_y * pitch + texel_x * BYTES_PER_PIXEL;
+                    let (r, g, b, a) = color.rgba();
+                    pixels[offset..offset + BYTES_PER_PIXEL].copy_from_slice(&[r, g, b, a]);
+                };
+                let visible = |cell: &Vec2Isize| {
+                    (min.x..=max.x).contains(&cell.x) && (min.y..=max.y).contains(&cell.y)
+                };
+                for (cell, color) in paint.trails().filter(|(cell, _)| visible(cell)) {
+                    put(cell, color);
+                }
+                for cell in grid.cells_in(min, max) {
+                    put(cell, paint.live(cell));
+                }
+            })
+        } else {
+            let cells_per_pixel = (1.0 / viewstate.zoom).powi(2);
+            let coverage = &self.coverage;
+            texture.with_lock(lock_rect, |pixels, pitch| {
+                let rows = pixels.chunks_mut(pitch).take(rows as usize);
+                for (row, counts) in rows.zip(coverage.chunks(cols as usize)) {
+                    let texels = row[..cols as usize * BYTES_PER_PIXEL].chunks_mut(BYTES_PER_PIXEL);
+                    for (texel, &count) in texels.zip(counts) {
+                        // Square root so sparse regions stay visible next to dense ones.
+                        let density = (count / cells_per_pixel).min(1.0).sqrt();
+                        let alpha = (a as f32 * density).round() as u8;
+                        texel.copy_from_slice(&[r, g, b, alpha]);
+                    }
+                }
+            })
+        };
+        if let Err(e) = written {
+            warn!("Couldn't update cell texture: {}", e);
+            return;
+        }
+
+        let src = FRect::new(0.0, 0.0, cols as f32, rows as f32);
+        canvas.copy(texture, src, layout.dst).unwrap();
+    }
+
+    /// Counts the live cells landing on each screen pixel, reading whole blocks from the
+    /// deepest pyramid level whose blocks are still no larger than a pixel.
+    fn accumulate_coverage(
+        &mut self,
+        grid: &Grid,
+        pyramid: &mut DensityPyramid,
+        width: u32,
+        height: u32,
+        viewstate: ViewState,
+    ) {
+        let zoom = viewstate.zoom;
+        let (min, max) = viewstate.visible_cells((width, height));
+        let (width, height) = (width as usize, height as usize);
+        self.coverage.clear();
+        self.coverage.resize(width * height, 0.0);
+
+        let level = ((1.0 / zoom).log2().floor() as u32).min(MAX_LEVEL);
+        let block_size = (1isize << level) as f32;
+        let (block_min, block_max) = (
+            Vec2Isize::new(min.x >> level, min.y >> level),
+            Vec2Isize::new(max.x >> level, max.y >> level),
+        );
+
+        let mut add = |block: Vec2Isize, count: u32| {
+            let corner = Vector2::new(block.x as f32 * block_size, block.y as f32 * block_size);
+            let Vector2 { x, y } = viewstate.world_to_screen(corner);
+            let (x, y) = (x.floor(), y.floor());
+            if (0.0..width as f32).contains(&x) && (0.0..height as f32).contains(&y) {
+                self.coverage[y as usize * width + x as usize] += count as f32;
+            }
+        };
+
+        if level == 0 {
+            for cell in grid.cells_in(min, max) {
+                add(cell, 1);
+            }
+        } else {
+            for (block, count) in pyramid.blocks_in(grid, level, block_min, block_max) {
+                add(block, count);
+            }
+        }
+    }
+
+    /// Makes sure the texture is at least `size` texels large, replacing it if it's too small.
+    fn reserve(&mut self, size: (u32, u32)) -> bool {
+        let (capacity_w, capacity_h) = self.capacity;
+        if self.texture.is_some() && size.0 <= capacity_w && size.1 <= capacity_h {
+            return true;
+        }
+
+        let new_capacity = (size.0.max(capacity_w), size.1.max(capacity_h));
+        let format = PixelFormat::from(TEXEL_FORMAT);
+        match self
+            .texture_creator
+            .create_texture_streaming(format, new_capacity.0, new_capacity.1)
+        {
+            Ok(mut texture) => {
+                texture.set_blend_mode(BlendMode::Blend);
+                texture.set_scale_mode(ScaleMode::Nearest);
+                self.texture = Some(texture);
+                self.capacity = new_capacity;
+                true
+            }
+            Err(e) => {
+                warn!(
+                    "Couldn't create {}x{} cell texture: {}",
+                    new_capacity.0, new_capacity.1, e
+                );
+                false
+            }
+        }
+    }
+}
+
+fn layout(width: u32, height: u32, viewstate: ViewState) -> Option<Layout> {
+    if width == 0 || height == 0 {
+        return None;
+    }
+    let zoom = viewstate.zoom;
+
+    if zoom < 1.0 {
+        return Some(Layout {
+            size: (width, height),
+            origin: Vec2Isize::new(0, 0),
+            dst: FRect::new(0.0, 0.0, width as f32, height as f32),
+        });
+    }
+
+    let (min, max) = viewstate.visible_cells((width, height));
+    let cols = (max.x - min.x + 1) as u32;
+    let rows = (max.y - min.y + 1) as u32;
+    let corner = viewstate.cell_to_screen(min);
+    Some(Layout {
+        size: (cols, rows),
+        origin: min,
+        dst: FRect::new(corner.x, corner.y, cols as f32 * zoom, rows as f32 * zoom),
+    })
+}
@@ -0,0 +1,145 @@
+use lexopt::prelude::*;
+use crate::follow::FollowTarget;
+use crate::pacing::{MAX_STEP_EXPONENT, Rate};
+use rust_sdl_gol::rule::Rule;
+use std::path::PathBuf;
+use std::process;
+
+const HELP: &str = "\
+Game of Life viewer
+
+usage: rust_sdl_gol [options] [pattern]
+       rust_sdl_gol run --help      headless batch runs
+
+arguments:
+  [pattern]              RLE (.rle) or plaintext (.cells) pattern to open
+
+options:
+  --rule <rule>          rule in B/S notation, e.g. B36/S23 [default: the pattern's, or B3/S23]
+  --size <w>x<h>         window size in pixels [default: 800x600]
+  --fullscreen           start fullscreen
+  --zoom <pixels>        pixels per cell [default: from config, 4]
+  --camera <x>,<y>       cell to centre the view on [default: 0,0]
+  --running              start with the simulation running
+  --paused               start with the simulation paused (default)
+  --gps <rate>           target generations per second, or `max` for as fast as
+                         possible [default: from config, 30]
+  --step <k>             advance 2^k generations per update [default: from config, 0]
+  --threads <n>          worker threads for the simulation [default: one per core]
+  --font <path>          TTF font for the HUD [default: from config, else searched for]
+  --follow <target>      keep the view on `centroid` or moving at a velocity like
+                         `c/4 se` or `2c/5 w` (directions n, ne, e, ... with n up)
+  --theme <name>         colour theme: dark, light, high-contrast, colour-blind or one
+                         defined in the config file [default: from config, dark]
+  -h, --help             print this help
+";
+
+#[derive(Debug, Clone)]
+pub struct Options {
+    pub pattern: Option<PathBuf>,
+    pub rule: Option<Rule>,
+    pub window_size: (u32, u32),
+    pub fullscreen: bool,
+    pub zoom: Option<f32>,
+    pub camera: (f32, f32),
+    pub running: bool,
+    pub gps: Option<Rate>,
+    pub step_exponent: Option<u32>,
+    pub threads: Option<usize>,
+    pub font: Option<PathBuf>,
+    pub theme: Option<String>,
+    pub follow: Option<FollowTarget>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            pattern: None,
+            rule: None,
+            window_size: (800, 600),
+            fullscreen: false,
+            zoom: None,
+            camera: (0.0, 0.0),
+            running: false,
+            gps: None,
+            step_exponent: None,
+            threads: None,
+            font: None,
+            theme: None,
+            follow: None,
+        }
+    }
+}
+
+fn parse_pair<T: std::str::FromStr>(value: &str, separator: char) -> Result<(T, T), String> {
+    let invalid = || format!("expected two values separated by `{}`", separator);
+    let (a, b) = value.split_once(separator).ok_or_else(invalid)?;
+    Ok((
+        a.trim().parse().map_err(|_| invalid())?,
+        b.trim().parse().map_err(|_| invalid())?,
+    ))
+}
+
+fn parse_size(value: &str) -> Result<(u32, u32), String> {
+    match parse_pair::<u32>(value, 'x')? {
+        (0, _) | (_, 0) => Err("window size must be positive".to_string()),
+        size => Ok(size),
+    }
+}
+
+fn parse_zoom(value: &str) -> Result<f32, String> {
+    match value.parse::<f32>() {
+        Ok(zoom) if (0.0001..=10000.0).contains(&zoom) => Ok(zoom),
+        _ => Err("zoom must be a number between 0.0001 and 10000".to_string()),
+    }
+}
+
+fn parse_step(value: &str) -> Result<u32, String> {
+    match value.parse::<u32>() {
+        Ok(k) if k <= MAX_STEP_EXPONENT => Ok(k),
+        _ => Err(format!(
+            "step exponent k must be between 0 and {} (2^k generations per update)",
+            MAX_STEP_EXPONENT
+        )),
+    }
+}
+
+fn parse_threads(value: &str) -> Result<usize, String> {
+    match value.parse::<usize>() {
+        Ok(threads) if threads > 0 => Ok(threads),
+        _ => Err("thread count must be a positive integer".to_string()),
+    }
+}
+
+pub fn parse_args<I: IntoIterator<Item = String>>(args: I) -> Result<Options, lexopt::Error> {
+    let mut options = Options::default();
+    let mut parser = lexopt::Parser::from_args(args);
+
+    while let Some(arg) = parser.next()? {
+        match arg {
+            Short('h') | Long("help") => {
+                print!("{}", HELP);
+                process::exit(0);
+            }
+            Long("rule") => options.rule = Some(parser.value()?.parse()?),
+            Long("size") => options.window_size = parser.value()?.parse_with(parse_size)?,
+            Long("fullscreen") => options.fullscreen = true,
+            Long("zoom") => options.zoom = Some(parser.value()?.parse_with(parse_zoom)?),
+            Long("camera") => {
+                options.camera = parser.value()?.parse_with(|v| parse_pair::<f32>(v, ','))?
+            }
+            Long("running") => options.running = true,
+            Long("paused") => options.running = false,
+            Long("gps") => options.gps = Some(parser.value()?.parse()?),
+            Long("step") => options.step_exponent = Some(parser.value()?.parse_with(parse_step)?),
+            Long("threads") => options.threads = Some(parser.value()?.parse_with(parse_threads)?),
+            Long("font") => options.font = Some(parser.value()?.into()),
+            Long("theme") => options.theme = Some(parser.value()?.string()?),
+            Long("follow") => options.follow = Some(parser.value()?.parse()?),
+            Value(path) if options.pattern.is_none() => options.pattern = Some(path.into()),
+            _ => return Err(arg.unexpected()),
+        }
+    }
+
+    Ok(options)
+}
@@ -0,0 +1,108 @@
+use crate::theme::{Theme, gradient};
+use rust_sdl_gol::gol::Vec2Isize;
+use rust_sdl_gol::history::{History, TRAIL_LENGTH};
+use sdl3::pixels::Color;
+
+/// Age at which a cell reaches the last colour of the age gradient.
+const OLD_AGE: f32 = 256.0;
+
+/// Peak opacity of a trail cell in the generation right after it died.
+const TRAIL_OPACITY: f32 = 0.6;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ColorMode {
+    /// Every live cell in the theme's cell colour.
+    #[default]
+    Plain,
+    /// Along the theme's age gradient, from newborn to long-lived cells.
+    Age,
+    /// Along the theme's heat gradient, from settled cells to ones that keep toggling.
+    Heat,
+    /// Cells born this generation stand out from those that survived.
+    Births,
+    /// Recently dead cells fade out behind the live ones.
+    Trails,
+}
+
+impl ColorMode {
+    pub const ALL: [ColorMode; 5] = [
+        ColorMode::Plain,
+        ColorMode::Age,
+        ColorMode::Heat,
+        ColorMode::Births,
+        ColorMode::Trails,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ColorMode::Plain => "plain",
+            ColorMode::Age => "age",
+            ColorMode::Heat => "heat",
+            ColorMode::Births => "births",
+            ColorMode::Trails => "trails",
+        }
+    }
+
+    pub fn named(name: &str) -> Option<ColorMode> {
+        ColorMode::ALL.into_iter().find(|mode| mode.name() == name)
+    }
+
+    pub fn next(self) -> ColorMode {
+        let index = ColorMode::ALL
+            .iter()
+            .position(|mode| *mode == self)
+            .unwrap();
+        ColorMode::ALL[(index + 1) % ColorMode::ALL.len()]
+    }
+
+    /// Whether the mode reads per-cell history, which the simulation only keeps when asked.
+    pub fn needs_history(self) -> bool {
+        self != ColorMode::Plain
+    }
+}
+
+/// Decides the colour of each cell for one frame.
+#[derive(Debug, Clone, Copy)]
+pub struct CellPaint<'a> {
+    pub mode: ColorMode,
+    pub theme: &'a Theme,
+    pub history: Option<&'a History>,
+}
+
+impl CellPaint<'_> {
+    pub fn live(&self, cell: Vec2Isize) -> Color {
+        let base = self.theme.cell;
+        let Some(history) = self.history else {
+            return base;
+        };
+
+        match self.mode {
+            ColorMode::Plain | ColorMode::Trails => base,
+            ColorMode::Age => {
+                let age = history.age(cell).max(1) as f32;
+                let t = (age.ln() / OLD_AGE.ln()).min(1.0);
+                gradient(&self.theme.age, t, base)
+            }
+            ColorMode::Heat => gradient(&self.theme.heat, history.heat(cell), base),
+            ColorMode::Births if history.is_birth(cell) => self.theme.birth,
+            ColorMode::Births => base,
+        }
+    }
+
+    /// Recently dead cells to draw under the live ones, with their faded colour.
+    pub fn trails(&self) -> impl Iterator<Item = (Vec2Isize, Color)> + '_ {
+        let history = self.history.filter(|_| self.mode == ColorMode::Trails);
+        let theme = self.theme;
+        history.into_iter().flat_map(move |history| {
+            history.trails().map(move |(cell, since)| {
+                let age = (since - 1) as f32 / TRAIL_LENGTH as f32;
+                let color = gradient(&theme.trail, age, theme.cell);
+                let alpha = color.a as f32 * TRAIL_OPACITY * (1.0 - age);
+                (
+                    cell,
+                    Color::RGBA(color.r, color.g, color.b, alpha.round() as u8),
+                )
+            })
+        })
+    }
+}
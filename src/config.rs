@@ -0,0 +1,515 @@
+use crate::brush::BrushShape;
+use crate::coloring::ColorMode;
+use crate::theme::{parse_hex_color, Theme};
+use log::warn;
+use sdl3::keyboard::Keycode;
+use sdl3::pixels::Color;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    Quit,
+    Clear,
+    OpenBrowser,
+    Pause,
+    Step,
+    PanUp,
+    PanDown,
+    PanLeft,
+    PanRight,
+    SpeedUp,
+    SlowDown,
+    BiggerSteps,
+    SmallerSteps,
+    ZoomIn,
+    ZoomOut,
+    CycleColorMode,
+    BrushBigger,
+    BrushSmaller,
+    CycleBrushShape,
+    ToggleInvertBrush,
+    BrushTool,
+    LineTool,
+    RectTool,
+    EllipseTool,
+    FillTool,
+    SelectTool,
+    RandomFill,
+    ToggleGrid,
+    ToggleMinimap,
+    Follow,
+    Fit,
+    ResetOrigin,
+    GoTo,
+    Bookmarks,
+}
+
+/// SDL key names for every action, e.g. `"Space"`, `"Left Shift"` or `"F1"`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct KeyBindings {
+    pub quit: String,
+    pub clear: String,
+    pub open_browser: String,
+    pub pause: String,
+    pub step: String,
+    pub pan_up: String,
+    pub pan_down: String,
+    pub pan_left: String,
+    pub pan_right: String,
+    pub speed_up: String,
+    pub slow_down: String,
+    pub bigger_steps: String,
+    pub smaller_steps: String,
+    pub zoom_in: String,
+    pub zoom_out: String,
+    pub cycle_color_mode: String,
+    pub brush_bigger: String,
+    pub brush_smaller: String,
+    pub cycle_brush_shape: String,
+    pub toggle_invert_brush: String,
+    pub brush_tool: String,
+    pub line_tool: String,
+    /// Pressing it again toggles between hollow and filled rectangles.
+    pub rect_tool: String,
+    /// Pressing it again toggles between hollow and filled ellipses.
+    pub ellipse_tool: String,
+    pub fill_tool: String,
+    pub select_tool: String,
+    /// Fills the selection, or a rectangle mid-view, with a random soup.
+    pub random_fill: String,
+    pub toggle_grid: String,
+    pub toggle_minimap: String,
+    pub follow: String,
+    pub fit: String,
+    pub reset_origin: String,
+    pub go_to: String,
+    pub bookmarks: String,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        KeyBindings {
+            quit: "Escape".to_string(),
+            clear: "R".to_string(),
+            open_browser: "B".to_string(),
+            pause: "Space".to_string(),
+            step: "Tab".to_string(),
+            pan_up: "W".to_string(),
+            pan_down: "S".to_string(),
+            pan_left: "A".to_string(),
+            pan_right: "D".to_string(),
+            speed_up: "Up".to_string(),
+            slow_down: "Down".to_string(),
+            bigger_steps: ".".to_string(),
+            smaller_steps: ",".to_string(),
+            zoom_in: "=".to_string(),
+            zoom_out: "-".to_string(),
+            cycle_color_mode: "C".to_string(),
+            brush_bigger: "]".to_string(),
+            brush_smaller: "[".to_string(),
+            cycle_brush_shape: "N".to_string(),
+            toggle_invert_brush: "I".to_string(),
+            brush_tool: "F1".to_string(),
+            line_tool: "F2".to_string(),
+            rect_tool: "F3".to_string(),
+            ellipse_tool: "F4".to_string(),
+            fill_tool: "F5".to_string(),
+            select_tool: "F6".to_string(),
+            random_fill: "U".to_string(),
+            toggle_grid: "G".to_string(),
+            toggle_minimap: "M".to_string(),
+            follow: "T".to_string(),
+            fit: "F".to_string(),
+            reset_origin: "Home".to_string(),
+            go_to: "J".to_string(),
+            bookmarks: "K".to_string(),
+        }
+    }
+}
+
+impl KeyBindings {
+    fn entries(&self) -> [(Action, &str); 34] {
+        [
+            (Action::Quit, &self.quit),
+            (Action::Clear, &self.clear),
+            (Action::OpenBrowser, &self.open_browser),
+            (Action::Pause, &self.pause),
+            (Action::Step, &self.step),
+            (Action::PanUp, &self.pan_up),
+            (Action::PanDown, &self.pan_down),
+            (Action::PanLeft, &self.pan_left),
+            (Action::PanRight, &self.pan_right),
+            (Action::SpeedUp, &self.speed_up),
+            (Action::SlowDown, &self.slow_down),
+            (Action::BiggerSteps, &self.bigger_steps),
+            (Action::SmallerSteps, &self.smaller_steps),
+            (Action::ZoomIn, &self.zoom_in),
+            (Action::ZoomOut, &self.zoom_out),
+            (Action::CycleColorMode, &self.cycle_color_mode),
+            (Action::BrushBigger, &self.brush_bigger),
+            (Action::BrushSmaller, &self.brush_smaller),
+            (Action::CycleBrushShape, &self.cycle_brush_shape),
+            (Action::ToggleInvertBrush, &self.toggle_invert_brush),
+            (Action::BrushTool, &self.brush_tool),
+            (Action::LineTool, &self.line_tool),
+            (Action::RectTool, &self.rect_tool),
+            (Action::EllipseTool, &self.ellipse_tool),
+            (Action::FillTool, &self.fill_tool),
+            (Action::SelectTool, &self.select_tool),
+            (Action::RandomFill, &self.random_fill),
+            (Action::ToggleGrid, &self.toggle_grid),
+            (Action::ToggleMinimap, &self.toggle_minimap),
+            (Action::Follow, &self.follow),
+            (Action::Fit, &self.fit),
+            (Action::ResetOrigin, &self.reset_origin),
+            (Action::GoTo, &self.go_to),
+            (Action::Bookmarks, &self.bookmarks),
+        ]
+    }
+
+    /// Resolves the key names, falling back to the default key for any name SDL doesn't know.
+    ///
+    /// Keys changed from their defaults are placed first, so a key the user picked wins over a
+    /// default it collides with. An action whose key is already taken falls back to its default
+    /// key, and is left unbound if that is taken too.
+    pub fn keymap(&self) -> HashMap<Keycode, Action> {
+        let defaults = KeyBindings::default();
+        let (changed, unchanged): (Vec<_>, Vec<_>) = self
+            .entries()
+            .into_iter()
+            .zip(defaults.entries())
+            .partition(|((_, name), (_, default))| name != default);
+
+        let mut keymap = HashMap::new();
+        for ((action, name), (_, default)) in changed.into_iter().chain(unchanged) {
+            let keycode = Keycode::from_name(name).or_else(|| {
+                warn!("Unknown key `{}` for {:?}, using `{}`", name, action, default);
+                Keycode::from_name(default)
+            });
+            let Some(keycode) = keycode else {
+                continue;
+            };
+            let Some(&taken_by) = keymap.get(&keycode) else {
+                keymap.insert(keycode, action);
+                continue;
+            };
+            match Keycode::from_name(default).filter(|key| !keymap.contains_key(key)) {
+                Some(fallback) => {
+                    warn!(
+                        "Key `{}` is bound to both {:?} and {:?}, using `{}` for {:?}",
+                        name, taken_by, action, default, action
+                    );
+                    keymap.insert(fallback, action);
+                }
+                None => warn!(
+                    "Key `{}` is bound to both {:?} and {:?}, leaving {:?} unbound",
+                    name, taken_by, action, action
+                ),
+            }
+        }
+        keymap
+    }
+}
+
+/// Colour overrides applied on top of a theme, as `#rrggbb` or `#rrggbbaa`. Palettes are lists
+/// of such colours.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ColorOverrides {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub background: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cell: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hover_alive: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hover_dead: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub paused_text: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stamp: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub grid_line: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub grid_major: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub axis: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub viewport: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub panel: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub panel_selected: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub panel_text: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub panel_muted_text: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub birth: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub age: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub heat: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trail: Option<Vec<String>>,
+}
+
+impl ColorOverrides {
+    pub fn apply(&self, theme: &mut Theme) {
+        let fields = [
+            (&self.background, &mut theme.background),
+            (&self.cell, &mut theme.cell),
+            (&self.hover_alive, &mut theme.hover_alive),
+            (&self.hover_dead, &mut theme.hover_dead),
+            (&self.text, &mut theme.text),
+            (&self.paused_text, &mut theme.paused_text),
+            (&self.stamp, &mut theme.stamp),
+            (&self.grid_line, &mut theme.grid_line),
+            (&self.grid_major, &mut theme.grid_major),
+            (&self.axis, &mut theme.axis),
+            (&self.viewport, &mut theme.viewport),
+            (&self.panel, &mut theme.panel),
+            (&self.panel_selected, &mut theme.panel_selected),
+            (&self.panel_text, &mut theme.panel_text),
+            (&self.panel_muted_text, &mut theme.panel_muted_text),
+            (&self.birth, &mut theme.birth),
+        ];
+        for (value, color) in fields {
+            if let Some(value) = value {
+                match parse_hex_color(value) {
+                    Some(parsed) => *color = parsed,
+                    None => warn!("Ignoring invalid colour `{}` in config", value),
+                }
+            }
+        }
+
+        let palettes = [
+            (&self.age, &mut theme.age),
+            (&self.heat, &mut theme.heat),
+            (&self.trail, &mut theme.trail),
+        ];
+        for (values, palette) in palettes {
+            if let Some(values) = values {
+                let parsed: Option<Vec<Color>> = values.iter().map(|v| parse_hex_color(v)).collect();
+                match parsed {
+                    Some(colors) if !colors.is_empty() => *palette = colors,
+                    _ => warn!("Ignoring invalid palette {:?} in config", values),
+                }
+            }
+        }
+    }
+}
+
+/// Cell grid overlay drawn over the live cells.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GridLines {
+    pub show: bool,
+    /// Pixels per cell above which every cell boundary is drawn. At least 1, so lines are never
+    /// drawn closer together than a pixel.
+    pub min_zoom: f32,
+    /// Cells between major lines, which stay visible further out than the minor ones.
+    /// 0 turns them off.
+    pub major_every: u32,
+    /// Lines along row 0 and column 0, drawn whenever the grid is shown.
+    pub axes: bool,
+}
+
+impl Default for GridLines {
+    fn default() -> Self {
+        GridLines {
+            show: false,
+            min_zoom: 8.0,
+            major_every: 10,
+            axes: true,
+        }
+    }
+}
+
+impl GridLines {
+    /// Replaces a `min_zoom` below one pixel by the default.
+    fn validate(&mut self) {
+        if self.min_zoom.is_nan() || self.min_zoom < 1.0 {
+            let default = GridLines::default().min_zoom;
+            warn!(
+                "Grid min_zoom must be at least 1, not {}, using {}",
+                self.min_zoom, default
+            );
+            self.min_zoom = default;
+        }
+    }
+}
+
+/// The brush cells are drawn and erased with.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BrushSettings {
+    /// Cells across.
+    pub size: u32,
+    /// square or circle.
+    pub shape: String,
+}
+
+impl Default for BrushSettings {
+    fn default() -> Self {
+        BrushSettings {
+            size: 1,
+            shape: BrushShape::default().name().to_string(),
+        }
+    }
+}
+
+/// A theme defined in the config file, as a built-in theme with some colours replaced.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CustomTheme {
+    pub base: String,
+    #[serde(flatten)]
+    pub colors: ColorOverrides,
+}
+
+impl Default for CustomTheme {
+    fn default() -> Self {
+        CustomTheme {
+            base: "dark".to_string(),
+            colors: ColorOverrides::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// Target generations per second, or 0 to run as fast as possible.
+    pub gps: f32,
+    /// Generations advanced per update, as a power of two.
+    pub step_exponent: u32,
+    /// Pixels per cell at startup. Zooming while the viewer runs doesn't change it.
+    pub zoom: f32,
+    /// Waits for the display's refresh before showing each frame.
+    pub vsync: bool,
+    /// Most frames drawn per second, or 0 for no limit beyond vsync.
+    pub max_fps: u32,
+    /// Keyboard panning speed in screen pixels per second.
+    pub pan_speed: f32,
+    pub theme: String,
+    /// How live cells are coloured: plain, age, heat, births or trails.
+    pub color_mode: String,
+    pub grid: GridLines,
+    pub brush: BrushSettings,
+    pub minimap: bool,
+    /// Time constant, in seconds, of the camera easing towards a followed target. 0 keeps the
+    /// target exactly centred.
+    pub follow_smoothing: f32,
+    /// TTF font for the HUD, overriding the search of the system font directories.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub font: Option<PathBuf>,
+    /// Overrides applied to whichever theme is selected.
+    pub colors: ColorOverrides,
+    /// Themes selectable by name alongside the built-in ones.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub themes: BTreeMap<String, CustomTheme>,
+    pub keys: KeyBindings,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            gps: 30.0,
+            step_exponent: 0,
+            vsync: true,
+            max_fps: 120,
+            zoom: 4.0,
+            pan_speed: 800.0,
+            theme: "dark".to_string(),
+            color_mode: ColorMode::default().name().to_string(),
+            grid: GridLines::default(),
+            brush: BrushSettings::default(),
+            minimap: true,
+            follow_smoothing: 0.15,
+            font: None,
+            colors: ColorOverrides::default(),
+            themes: BTreeMap::new(),
+            keys: KeyBindings::default(),
+        }
+    }
+}
+
+impl Config {
+    /// `$XDG_CONFIG_HOME/rust_sdl_gol/config.toml`, or the platform equivalent.
+    pub fn path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("rust_sdl_gol").join("config.toml"))
+    }
+
+    /// Reads the config at `path`, returning the defaults if the file doesn't exist yet.
+    pub fn load(path: &Path) -> Result<Config, String> {
+        match fs::read_to_string(path) {
+            Ok(text) => {
+                let mut config: Config = toml::from_str(&text).map_err(|e| e.to_string())?;
+                config.grid.validate();
+                Ok(config)
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Config::default()),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let text = toml::to_string_pretty(self).map_err(|e| e.to_string())?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        fs::write(path, text).map_err(|e| e.to_string())
+    }
+
+    pub fn theme(&self) -> Theme {
+        self.theme_named(&self.theme).unwrap_or_else(|| {
+            warn!("Unknown theme `{}` in config, using dark", self.theme);
+            self.theme_named("dark").unwrap()
+        })
+    }
+
+    /// Looks `name` up among the custom themes, then the built-in ones, and applies the
+    /// top-level colour overrides to it.
+    pub fn theme_named(&self, name: &str) -> Option<Theme> {
+        let mut theme = match self.themes.get(name) {
+            Some(custom) => {
+                let mut theme = Theme::named(&custom.base).unwrap_or_else(|| {
+                    warn!("Unknown base theme `{}` for `{}`, using dark", custom.base, name);
+                    Theme::dark()
+                });
+                custom.colors.apply(&mut theme);
+                theme
+            }
+            None => Theme::named(name)?,
+        };
+        self.colors.apply(&mut theme);
+        Some(theme)
+    }
+
+    /// Every theme name `theme_named` accepts.
+    pub fn theme_names(&self) -> Vec<&str> {
+        let custom = self.themes.keys().map(String::as_str);
+        Theme::NAMES.into_iter().chain(custom).collect()
+    }
+
+    pub fn brush_shape(&self) -> BrushShape {
+        BrushShape::named(&self.brush.shape).unwrap_or_else(|| {
+            warn!("Unknown brush shape `{}` in config, using square", self.brush.shape);
+            BrushShape::default()
+        })
+    }
+
+    pub fn color_mode(&self) -> ColorMode {
+        ColorMode::named(&self.color_mode).unwrap_or_else(|| {
+            warn!("Unknown colour mode `{}` in config, using plain", self.color_mode);
+            ColorMode::default()
+        })
+    }
+}
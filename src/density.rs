@@ -0,0 +1,285 @@
+use rust_sdl_gol::gol::{Grid, TILE_SHIFT, TILE_SIZE, Vec2Isize};
+use std::collections::{BTreeMap, HashMap};
+
+/// Deepest level kept, where one block is 2^20 cells on a side.
+pub const MAX_LEVEL: u32 = 20;
+
+type Rows = [u64; TILE_SIZE];
+
+/// Live cell counts aggregated into power-of-two blocks, so a zoomed-out view visits one entry
+/// per block instead of every cell.
+///
+/// Level `k` counts the cells in each `2^k x 2^k` block, keyed by the cell coordinates shifted
+/// right by `k`. Levels are built on demand. When the grid changes, only the grid tiles that
+/// changed are counted again. Everything drawing the grid shares one pyramid, so no level is
+/// built twice for the same generation.
+#[derive(Debug, Default)]
+pub struct DensityPyramid {
+    version: Option<u64>,
+    /// The grid's tiles as of `version`, to tell which ones changed. Only kept while some level
+    /// is built.
+    tiles: HashMap<Vec2Isize, Rows>,
+    /// `levels[k - 1]` holds level `k`, keyed by block row then column so a range of columns in
+    /// one row is a single lookup. Level 0 is the grid itself.
+    levels: Vec<BTreeMap<(isize, isize), u32>>,
+}
+
+impl DensityPyramid {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Every block with live cells at `level`, which must be between 1 and `MAX_LEVEL`, and
+    /// its count.
+    pub fn blocks(&mut self, grid: &Grid, level: u32) -> impl Iterator<Item = (Vec2Isize, u32)> {
+        self.level(grid, level)
+            .iter()
+            .map(|(&(y, x), &count)| (Vec2Isize::new(x, y), count))
+    }
+
+    /// The blocks at `level` inside the inclusive block range `min..=max`, visiting only the
+    /// rows of blocks in the range rather than every block.
+    pub fn blocks_in(
+        &mut self,
+        grid: &Grid,
+        level: u32,
+        min: Vec2Isize,
+        max: Vec2Isize,
+    ) -> impl Iterator<Item = (Vec2Isize, u32)> {
+        let blocks = self.level(grid, level);
+        (min.y..=max.y).flat_map(move |y| {
+            blocks
+                .range((y, min.x)..=(y, max.x))
+                .map(|(&(y, x), &count)| (Vec2Isize::new(x, y), count))
+        })
+    }
+
+    /// Inclusive `(min, max)` corners of the live cells, rounded out to whole blocks at `level`,
+    /// or `None` if the grid is empty. Only the blocks are visited, not every cell, unless
+    /// `level` is 0.
+    pub fn bounds(&mut self, grid: &Grid, level: u32) -> Option<(Vec2Isize, Vec2Isize)> {
+        if level == 0 {
+            return grid.bounds();
+        }
+        let blocks = self.level(grid, level);
+        // Rows come sorted, so only the columns need searching.
+        let (&(min_y, _), _) = blocks.first_key_value()?;
+        let (&(max_y, _), _) = blocks.last_key_value()?;
+        let (min_x, max_x) = blocks
+            .keys()
+            .fold((isize::MAX, isize::MIN), |(min, max), &(_, x)| {
+                (min.min(x), max.max(x))
+            });
+        Some((
+            Vec2Isize::new(min_x << level, min_y << level),
+            Vec2Isize::new(((max_x + 1) << level) - 1, ((max_y + 1) << level) - 1),
+        ))
+    }
+
+    fn level(&mut self, grid: &Grid, level: u32) -> &BTreeMap<(isize, isize), u32> {
+        debug_assert!((1..=MAX_LEVEL).contains(&level));
+        self.sync(grid);
+        if self.levels.is_empty() {
+            self.tiles = grid.tiles().map(|(origin, rows)| (origin, *rows)).collect();
+        }
+        while self.levels.len() < level as usize {
+            let level = self.levels.len() as u32 + 1;
+            let mut blocks = BTreeMap::new();
+            for (origin, rows) in &self.tiles {
+                add_tile(&mut blocks, *origin, rows, level, true);
+            }
+            self.levels.push(blocks);
+        }
+        &self.levels[level as usize - 1]
+    }
+
+    /// Brings the built levels up to date with `grid`, recounting only the tiles that changed.
+    fn sync(&mut self, grid: &Grid) {
+        if self.version == Some(grid.version()) {
+            return;
+        }
+        self.version = Some(grid.version());
+        if self.levels.is_empty() {
+            self.tiles.clear();
+            return;
+        }
+
+        let current: HashMap<Vec2Isize, &Rows> = grid.tiles().collect();
+        let mut changes: Vec<(Vec2Isize, Option<Rows>, Option<Rows>)> = Vec::new();
+        for (origin, rows) in &current {
+            match self.tiles.get(origin) {
+                Some(old) if old == *rows => {}
+                old => changes.push((*origin, old.copied(), Some(**rows))),
+            }
+        }
+        for (origin, old) in &self.tiles {
+            if !current.contains_key(origin) {
+                changes.push((*origin, Some(*old), None));
+            }
+        }
+
+        for (origin, old, new) in changes {
+            for (index, blocks) in self.levels.iter_mut().enumerate() {
+                let level = index as u32 + 1;
+                if let Some(old) = &old {
+                    add_tile(blocks, origin, old, level, false);
+                }
+                if let Some(new) = &new {
+                    add_tile(blocks, origin, new, level, true);
+                }
+            }
+            match new {
+                Some(new) => self.tiles.insert(origin, new),
+                None => self.tiles.remove(&origin),
+            };
+        }
+    }
+}
+
+/// Adds the cells of the tile at tile coordinate `origin` to the block counts at `level`, or
+/// takes them away if not `add`.
+fn add_tile(
+    blocks: &mut BTreeMap<(isize, isize), u32>,
+    origin: Vec2Isize,
+    rows: &Rows,
+    level: u32,
+    add: bool,
+) {
+    let mut apply = |key: (isize, isize), count: u32| {
+        if count == 0 {
+            return;
+        }
+        let entry = blocks.entry(key).or_insert(0);
+        if add {
+            *entry += count;
+        } else {
+            *entry -= count;
+            if *entry == 0 {
+                blocks.remove(&key);
+            }
+        }
+    };
+
+    if level >= TILE_SHIFT {
+        // The whole tile lands in one block.
+        let shift = level - TILE_SHIFT;
+        let count = rows.iter().map(|row| row.count_ones()).sum();
+        apply((origin.y >> shift, origin.x >> shift), count);
+        return;
+    }
+
+    let size = 1usize << level;
+    let per_side = TILE_SIZE / size;
+    let mask = (1u64 << size) - 1;
+    let (base_x, base_y) = (
+        origin.x << (TILE_SHIFT - level),
+        origin.y << (TILE_SHIFT - level),
+    );
+    for (block_y, block_rows) in rows.chunks(size).enumerate() {
+        if block_rows.iter().all(|row| *row == 0) {
+            continue;
+        }
+        for block_x in 0..per_side {
+            let count = block_rows
+                .iter()
+                .map(|row| ((row >> (block_x * size)) & mask).count_ones())
+                .sum();
+            apply(
+                (base_y + block_y as isize, base_x + block_x as isize),
+                count,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sorted(blocks: impl Iterator<Item = (Vec2Isize, u32)>) -> Vec<((isize, isize), u32)> {
+        let mut blocks: Vec<_> = blocks
+            .map(|(block, count)| ((block.x, block.y), count))
+            .collect();
+        blocks.sort();
+        blocks
+    }
+
+    /// Block counts at `level` worked out cell by cell.
+    fn expected(grid: &Grid, level: u32) -> Vec<((isize, isize), u32)> {
+        let mut blocks = BTreeMap::new();
+        for cell in grid.iter() {
+            *blocks
+                .entry((cell.x >> level, cell.y >> level))
+                .or_insert(0) += 1;
+        }
+        blocks.into_iter().collect()
+    }
+
+    fn scattered() -> Grid {
+        (-400..400)
+            .step_by(3)
+            .map(|i| Vec2Isize::new(i, (i * 7) % 130 - 65))
+            .collect()
+    }
+
+    #[test]
+    fn levels_count_the_cells_in_each_block() {
+        let grid = scattered();
+        let mut pyramid = DensityPyramid::new();
+        for level in [1, 3, 6, 7, 12] {
+            assert_eq!(sorted(pyramid.blocks(&grid, level)), expected(&grid, level));
+        }
+    }
+
+    #[test]
+    fn changed_tiles_update_every_built_level() {
+        let mut grid = scattered();
+        let mut pyramid = DensityPyramid::new();
+        for level in 1..=8 {
+            pyramid.blocks(&grid, level).count();
+        }
+        // A cell added to a new tile, one removed, and a tile emptied entirely.
+        grid.set_cell(1000, -1000, true);
+        grid.set_cell(-400, (-400 * 7) % 130 - 65, false);
+        for cell in grid
+            .cells_in(Vec2Isize::new(0, -64), Vec2Isize::new(63, -1))
+            .collect::<Vec<_>>()
+        {
+            grid.set_cell(cell.x, cell.y, false);
+        }
+        for level in 1..=8 {
+            assert_eq!(
+                sorted(pyramid.blocks(&grid, level)),
+                expected(&grid, level),
+                "level {}",
+                level
+            );
+        }
+    }
+
+    #[test]
+    fn blocks_in_keeps_to_the_range() {
+        let grid = scattered();
+        let mut pyramid = DensityPyramid::new();
+        let (min, max) = (Vec2Isize::new(-10, -3), Vec2Isize::new(20, 2));
+        let inside: Vec<_> = expected(&grid, 4)
+            .into_iter()
+            .filter(|((x, y), _)| (min.x..=max.x).contains(x) && (min.y..=max.y).contains(y))
+            .collect();
+        assert!(!inside.is_empty());
+        assert_eq!(sorted(pyramid.blocks_in(&grid, 4, min, max)), inside);
+    }
+
+    #[test]
+    fn bounds_round_out_to_whole_blocks() {
+        let grid: Grid = [Vec2Isize::new(-3, 5), Vec2Isize::new(17, -20)]
+            .into_iter()
+            .collect();
+        let mut pyramid = DensityPyramid::new();
+        assert_eq!(
+            pyramid.bounds(&grid, 3),
+            Some((Vec2Isize::new(-8, -24), Vec2Isize::new(23, 7)))
+        );
+        assert_eq!(pyramid.bounds(&Grid::new(), 3), None);
+    }
+}
@@ -0,0 +1,409 @@
+use rust_sdl_gol::camera::{Vector2, ViewState};
+use rust_sdl_gol::gol::{GOL, Grid, Vec2Isize};
+use std::collections::HashSet;
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// Live cells at most this far apart on both axes count as one object, which keeps the common
+/// spaceships in one piece through all of their phases.
+const OBJECT_GAP: isize = 2;
+
+/// Objects stop growing at this many cells, so following a spot inside a large pattern stays cheap.
+const MAX_OBJECT_CELLS: usize = 4096;
+
+/// Furthest a tracked object is looked for from where it was, in cells per generation elapsed.
+const MAX_REACH: u64 = 64;
+
+const DIRECTIONS: [(&str, f32, f32); 8] = [
+    ("n", 0.0, -1.0),
+    ("ne", 1.0, -1.0),
+    ("e", 1.0, 0.0),
+    ("se", 1.0, 1.0),
+    ("s", 0.0, 1.0),
+    ("sw", -1.0, 1.0),
+    ("w", -1.0, 0.0),
+    ("nw", -1.0, -1.0),
+];
+
+/// A spaceship speed such as `c/4`, heading in a compass direction with north up the screen.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Velocity {
+    cells: u32,
+    period: u32,
+    direction: &'static str,
+}
+
+impl Velocity {
+    /// Cells moved per generation, as `(x, y)` with y down the screen.
+    fn per_generation(self) -> Vector2 {
+        let (_, x, y) = DIRECTIONS
+            .iter()
+            .find(|(name, _, _)| *name == self.direction)
+            .unwrap();
+        let speed = self.cells as f32 / self.period as f32;
+        Vector2::new(x * speed, y * speed)
+    }
+}
+
+/// Parses the usual speed notation followed by a direction, e.g. `c/4 se`, `2c/5 w` or `c/4d ne`.
+impl FromStr for Velocity {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, String> {
+        let invalid = || {
+            format!(
+                "invalid velocity `{}`, expected a speed and direction like `c/4 se` or `2c/5 w`",
+                value
+            )
+        };
+        let (speed, direction) = value
+            .trim()
+            .split_once(char::is_whitespace)
+            .ok_or_else(invalid)?;
+        let direction = direction.trim().to_ascii_lowercase();
+        let &(direction, x, y) = DIRECTIONS
+            .iter()
+            .find(|(name, _, _)| *name == direction)
+            .ok_or_else(|| {
+                format!(
+                    "unknown direction `{}`, expected n, ne, e, se, s, sw, w or nw",
+                    direction
+                )
+            })?;
+
+        let speed = speed.to_ascii_lowercase();
+        let (speed, diagonal) = match speed.strip_suffix('d') {
+            Some(speed) => (speed, Some(true)),
+            None => match speed.strip_suffix('o') {
+                Some(speed) => (speed, Some(false)),
+                None => (speed.as_str(), None),
+            },
+        };
+        if diagonal.is_some_and(|diagonal| diagonal != (x != 0.0 && y != 0.0)) {
+            return Err(format!(
+                "`{}` doesn't match the direction `{}`",
+                value.trim(),
+                direction
+            ));
+        }
+        let (cells, period) = speed.split_once('/').unwrap_or((speed, "1"));
+        let cells = match cells.strip_suffix('c').ok_or_else(invalid)? {
+            "" => 1,
+            cells => cells.parse::<u32>().map_err(|_| invalid())?,
+        };
+        let period = period.parse::<u32>().map_err(|_| invalid())?;
+        if cells == 0 || period == 0 || cells > period {
+            return Err("speed must be more than 0 and at most c".to_string());
+        }
+        Ok(Velocity {
+            cells,
+            period,
+            direction,
+        })
+    }
+}
+
+impl fmt::Display for Velocity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.cells != 1 {
+            write!(f, "{}", self.cells)?;
+        }
+        write!(f, "c")?;
+        if self.period != 1 {
+            write!(f, "/{}", self.period)?;
+        }
+        write!(f, " {}", self.direction)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FollowTarget {
+    /// The mean position of every live cell.
+    Centroid,
+    /// The object that was under the cursor, tracked from generation to generation.
+    Object,
+    /// A point moving at a fixed velocity from where the view was centred.
+    Velocity(Velocity),
+}
+
+/// Parses `centroid` or a velocity. `object` is refused: it follows whatever is under the
+/// cursor when following starts, so it can only be picked with the follow key, not from the
+/// command line.
+impl FromStr for FollowTarget {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, String> {
+        match value.trim() {
+            "centroid" => Ok(FollowTarget::Centroid),
+            "object" => Err(
+                "`object` follows what's under the cursor, so start it with the follow key"
+                    .to_string(),
+            ),
+            velocity => velocity.parse().map(FollowTarget::Velocity),
+        }
+    }
+}
+
+impl fmt::Display for FollowTarget {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FollowTarget::Centroid => write!(f, "centroid"),
+            FollowTarget::Object => write!(f, "object"),
+            FollowTarget::Velocity(velocity) => write!(f, "{}", velocity),
+        }
+    }
+}
+
+/// Keeps the view centred on a moving target as the simulation runs.
+pub struct Follow {
+    target: Option<FollowTarget>,
+    /// Where the view is heading, in cells, as `(x, y)`.
+    goal: Vector2,
+    /// Inclusive bounds of the tracked object, where to look for it in the next generation.
+    object: Option<(Vec2Isize, Vec2Isize)>,
+    /// Generation and view centre when following started, which a velocity lock moves from.
+    anchor: (u64, Vector2),
+    /// Grid version and generation the goal was last worked out for.
+    seen: Option<(u64, u64)>,
+}
+
+impl Follow {
+    pub fn new() -> Self {
+        Follow {
+            target: None,
+            goal: Vector2::default(),
+            object: None,
+            anchor: (0, Vector2::default()),
+            seen: None,
+        }
+    }
+
+    pub fn target(&self) -> Option<FollowTarget> {
+        self.target
+    }
+
+    /// Starts following `target`. An object is picked from under the cursor, falling back to the
+    /// centroid if there is none.
+    pub fn start(
+        &mut self,
+        target: FollowTarget,
+        gol: &GOL,
+        viewstate: &ViewState,
+        output_size: (u32, u32),
+    ) {
+        let center = viewstate.center(output_size);
+        self.target = Some(target);
+        self.goal = center;
+        self.anchor = (gol.generation, center);
+        self.seen = None;
+        self.object = None;
+
+        if target == FollowTarget::Object {
+            let cursor = viewstate.cursor_cell();
+            match find_object(&gol.grid, (cursor, cursor), OBJECT_GAP) {
+                Some((_, bounds)) => self.object = Some(bounds),
+                None => self.target = Some(FollowTarget::Centroid),
+            }
+        }
+    }
+
+    pub fn stop(&mut self) {
+        self.target = None;
+        self.object = None;
+    }
+
+    /// Moves the camera towards the target, easing with the time constant `smoothing` in seconds.
+    pub fn update(
+        &mut self,
+        gol: &GOL,
+        viewstate: &mut ViewState,
+        output_size: (u32, u32),
+        frame_time: Duration,
+        smoothing: f32,
+    ) {
+        let Some(target) = self.target else {
+            return;
+        };
+
+        let seen = (gol.grid.version(), gol.generation);
+        if self.seen != Some(seen) {
+            let elapsed = self.seen.map_or(0, |(_, generation)| {
+                gol.generation.saturating_sub(generation)
+            });
+            self.seen = Some(seen);
+            match target {
+                FollowTarget::Centroid => {
+                    if let Some(centroid) = centroid(gol.grid.iter()) {
+                        self.goal = centroid;
+                    }
+                }
+                FollowTarget::Object => {
+                    // A lost object leaves the view where it was last seen.
+                    let reach = OBJECT_GAP + elapsed.min(MAX_REACH) as isize;
+                    if let Some(bounds) = self.object
+                        && let Some((centroid, bounds)) = find_object(&gol.grid, bounds, reach)
+                    {
+                        self.goal = centroid;
+                        self.object = Some(bounds);
+                    }
+                }
+                FollowTarget::Velocity(velocity) => {
+                    let (generation, center) = self.anchor;
+                    let generations = gol.generation.saturating_sub(generation) as f32;
+                    let step = velocity.per_generation();
+                    self.goal = Vector2::new(
+                        center.x + step.x * generations,
+                        center.y + step.y * generations,
+                    );
+                }
+            }
+        }
+
+        let center = viewstate.center(output_size);
+        let t = if smoothing > 0.0 {
+            1.0 - (-frame_time.as_secs_f32() / smoothing).exp()
+        } else {
+            1.0
+        };
+        viewstate.center_on(
+            center.x + (self.goal.x - center.x) * t,
+            center.y + (self.goal.y - center.y) * t,
+            output_size,
+        );
+    }
+}
+
+/// Mean position of `cells`, measured to cell centres.
+fn centroid(cells: impl Iterator<Item = Vec2Isize>) -> Option<Vector2> {
+    let (count, sum_x, sum_y) = cells.fold((0usize, 0.0f64, 0.0f64), |(count, x, y), cell| {
+        (count + 1, x + cell.x as f64, y + cell.y as f64)
+    });
+    if count == 0 {
+        return None;
+    }
+    Some(Vector2::new(
+        (sum_x / count as f64) as f32 + 0.5,
+        (sum_y / count as f64) as f32 + 0.5,
+    ))
+}
+
+/// Grows an object from the live cells within `reach` of the inclusive `(min, max)` area and
+/// returns its centroid and bounds.
+fn find_object(
+    grid: &Grid,
+    (min, max): (Vec2Isize, Vec2Isize),
+    reach: isize,
+) -> Option<(Vector2, (Vec2Isize, Vec2Isize))> {
+    let grow = |min: Vec2Isize, max: Vec2Isize, by: isize| {
+        (
+            Vec2Isize::new(min.x - by, min.y - by),
+            Vec2Isize::new(max.x + by, max.y + by),
+        )
+    };
+
+    let (seed_min, seed_max) = grow(min, max, reach);
+    let mut pending: Vec<Vec2Isize> = grid
+        .cells_in(seed_min, seed_max)
+        .take(MAX_OBJECT_CELLS)
+        .collect();
+    let mut object: HashSet<Vec2Isize> = pending.iter().copied().collect();
+    while let Some(cell) = pending.pop() {
+        if object.len() >= MAX_OBJECT_CELLS {
+            break;
+        }
+        let (near_min, near_max) = grow(cell, cell, OBJECT_GAP);
+        for near in grid.cells_in(near_min, near_max) {
+            if object.insert(near) {
+                pending.push(near);
+            }
+        }
+    }
+
+    let centroid = centroid(object.iter().copied())?;
+    let first = *object.iter().next()?;
+    let bounds = object.iter().fold((first, first), |(min, max), cell| {
+        (
+            Vec2Isize::new(min.x.min(cell.x), min.y.min(cell.y)),
+            Vec2Isize::new(max.x.max(cell.x), max.y.max(cell.y)),
+        )
+    });
+    Some((centroid, bounds))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn velocity(cells: u32, period: u32, direction: &'static str) -> Velocity {
+        Velocity {
+            cells,
+            period,
+            direction,
+        }
+    }
+
+    #[test]
+    fn velocities_parse() {
+        assert_eq!("c/4 se".parse(), Ok(velocity(1, 4, "se")));
+        assert_eq!("2c/5 w".parse(), Ok(velocity(2, 5, "w")));
+        assert_eq!("c/4d NE".parse(), Ok(velocity(1, 4, "ne")));
+        assert_eq!("c/2o n".parse(), Ok(velocity(1, 2, "n")));
+        assert_eq!("  17c/45   s ".parse(), Ok(velocity(17, 45, "s")));
+        assert_eq!("c e".parse(), Ok(velocity(1, 1, "e")));
+    }
+
+    #[test]
+    fn velocities_round_trip_through_display() {
+        for text in ["c/4 se", "2c/5 w", "c nw", "17c/45 s", "c/2 n"] {
+            let velocity: Velocity = text.parse().unwrap();
+            assert_eq!(velocity.to_string(), text);
+            assert_eq!(velocity.to_string().parse(), Ok(velocity));
+        }
+        // The diagonal and orthogonal suffixes only check the direction.
+        assert_eq!("c/4d sw".parse::<Velocity>().unwrap().to_string(), "c/4 sw");
+    }
+
+    #[test]
+    fn suffixes_must_match_the_direction() {
+        assert!("c/4d e".parse::<Velocity>().is_err());
+        assert!("c/4o se".parse::<Velocity>().is_err());
+    }
+
+    #[test]
+    fn malformed_velocities_are_rejected() {
+        for text in [
+            "", "c/4", "c/4 up", "4 se", "c/x se", "xc/4 se", "c/ se", "c/4dd se", "c/4 s e",
+        ] {
+            assert!(text.parse::<Velocity>().is_err(), "`{}`", text);
+        }
+    }
+
+    #[test]
+    fn speeds_are_above_zero_and_at_most_c() {
+        assert!("2c/3 n".parse::<Velocity>().is_ok());
+        assert!("3c/2 n".parse::<Velocity>().is_err());
+        assert!("2c n".parse::<Velocity>().is_err());
+        assert!("0c/4 n".parse::<Velocity>().is_err());
+        assert!("c/0 n".parse::<Velocity>().is_err());
+    }
+
+    #[test]
+    fn diagonal_velocities_move_on_both_axes() {
+        let step = velocity(1, 4, "se").per_generation();
+        assert_eq!((step.x, step.y), (0.25, 0.25));
+        let step = velocity(2, 5, "n").per_generation();
+        assert_eq!((step.x, step.y), (0.0, -0.4));
+    }
+
+    #[test]
+    fn follow_targets_round_trip() {
+        for target in [
+            FollowTarget::Centroid,
+            FollowTarget::Velocity(velocity(1, 4, "sw")),
+        ] {
+            assert_eq!(target.to_string().parse(), Ok(target));
+        }
+        assert!("object".parse::<FollowTarget>().is_err());
+    }
+}
@@ -0,0 +1,133 @@
+use log::{info, warn};
+use sdl3::iostream::IOStream;
+use sdl3::ttf::{Font, Sdl3TtfContext};
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Size used to check that a font file actually loads before committing to it.
+const PROBE_SIZE: f32 = 32.0;
+
+/// DejaVu Sans Mono, used when no preferred font is installed.
+const EMBEDDED_FONT: &[u8] = include_bytes!("../assets/fonts/DejaVuSansMono.ttf");
+
+/// Font files looked for in the system font directories, most preferred first.
+const PREFERRED_FONTS: &[&str] = &[
+    "JetBrainsMono-Regular.ttf",
+    "DejaVuSansMono.ttf",
+    "LiberationMono-Regular.ttf",
+    "NotoSansMono-Regular.ttf",
+    "DejaVuSans.ttf",
+];
+
+/// How deep to recurse into font directories, which are usually grouped by vendor or family.
+const MAX_SEARCH_DEPTH: usize = 4;
+
+/// Where HUD text comes from. TTF sources are loaded again for every size text is drawn at.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FontSource {
+    Path(PathBuf),
+    Embedded,
+    /// Built-in bitmap glyphs drawn as rectangles, used when no TTF font can be loaded at all.
+    Bitmap,
+}
+
+impl FontSource {
+    pub fn load(&self, ttf_context: &Sdl3TtfContext, size: f32) -> Result<Font<'static>, String> {
+        let font = match self {
+            FontSource::Path(path) => ttf_context.load_font(path, size),
+            FontSource::Embedded => IOStream::from_bytes(EMBEDDED_FONT)
+                .and_then(|stream| ttf_context.load_font_from_iostream(stream, size)),
+            FontSource::Bitmap => return Err("the bitmap font has no TTF data".to_string()),
+        };
+        font.map_err(|e| e.to_string())
+    }
+}
+
+/// Font directories in the order fontconfig searches them by default.
+fn font_dirs() -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    let home = env::var_os("HOME").map(PathBuf::from);
+
+    match env::var_os("XDG_DATA_HOME") {
+        Some(data_home) => dirs.push(PathBuf::from(data_home).join("fonts")),
+        None => dirs.extend(home.iter().map(|home| home.join(".local/share/fonts"))),
+    }
+    dirs.extend(home.iter().map(|home| home.join(".fonts")));
+
+    let data_dirs = env::var("XDG_DATA_DIRS")
+        .ok()
+        .filter(|dirs| !dirs.is_empty())
+        .unwrap_or_else(|| "/usr/local/share:/usr/share".to_string());
+    dirs.extend(data_dirs.split(':').map(|dir| Path::new(dir).join("fonts")));
+
+    dirs.push(PathBuf::from("/Library/Fonts"));
+    dirs.push(PathBuf::from("C:\\Windows\\Fonts"));
+    dirs
+}
+
+/// Records in `found` the first path seen for each of `PREFERRED_FONTS`, looking at the files
+/// in `dir` before its subdirectories.
+fn search_dir(dir: &Path, depth: usize, found: &mut [Option<PathBuf>]) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    let mut subdirs = Vec::new();
+    for path in entries.flatten().map(|entry| entry.path()) {
+        if path.is_dir() {
+            subdirs.push(path);
+        } else if let Some(name) = path.file_name().and_then(|name| name.to_str())
+            && let Some(index) = PREFERRED_FONTS.iter().position(|font| *font == name)
+            && found[index].is_none()
+        {
+            found[index] = Some(path);
+        }
+    }
+    if depth > 0 {
+        for subdir in subdirs {
+            search_dir(&subdir, depth - 1, found);
+        }
+    }
+}
+
+/// Finds the most preferred font installed on the system, walking the font directories once.
+pub fn find_system_font() -> Option<PathBuf> {
+    let mut found = vec![None; PREFERRED_FONTS.len()];
+    for dir in font_dirs() {
+        // Nothing later can beat the most preferred font.
+        if found[0].is_some() {
+            break;
+        }
+        search_dir(&dir, MAX_SEARCH_DEPTH, &mut found);
+    }
+    found.into_iter().flatten().next()
+}
+
+/// Picks the configured font, then the best system font, then the embedded font, and gives up
+/// on TTF entirely only if all of those fail to load.
+pub fn find_font(ttf_context: Option<&Sdl3TtfContext>, configured: Option<&Path>) -> FontSource {
+    let Some(ttf_context) = ttf_context else {
+        warn!("TTF unavailable, using the built-in bitmap font");
+        return FontSource::Bitmap;
+    };
+
+    // The system search walks directories, so only do it if the configured font fails.
+    let candidates = configured
+        .map(Path::to_path_buf)
+        .into_iter()
+        .chain(std::iter::once_with(find_system_font).flatten())
+        .map(FontSource::Path)
+        .chain(std::iter::once(FontSource::Embedded));
+    for source in candidates {
+        match source.load(ttf_context, PROBE_SIZE) {
+            Ok(_) => {
+                info!("using font {:?}", source);
+                return source;
+            }
+            Err(e) => warn!("Couldn't load font {:?}: {}", source, e),
+        }
+    }
+
+    warn!("No TTF font could be loaded, using the built-in bitmap font");
+    FontSource::Bitmap
+}
@@ -0,0 +1,275 @@
+use crate::gol::Grid;
+use crate::rule::Rule;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl ParseError {
+    fn new(line: usize, message: impl Into<String>) -> Self {
+        ParseError {
+            line,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+#[derive(Debug, Clone)]
+pub struct PatternData {
+    pub name: Option<String>,
+    pub rule: Option<String>,
+    pub grid: Grid,
+}
+
+/// Longest run an RLE pattern may give. Longer runs are refused rather than spending minutes
+/// placing cells.
+pub const MAX_RUN_LENGTH: isize = 1 << 20;
+
+/// Parses a pattern in run length encoded (RLE) format.
+pub fn parse_rle(text: &str) -> Result<PatternData, ParseError> {
+    let mut name = None;
+    let mut rule = None;
+    let mut grid = Grid::new();
+
+    let mut header_seen = false;
+    let mut run: Option<isize> = None;
+    let (mut row, mut col) = (0isize, 0isize);
+
+    'lines: for (index, line) in text.lines().enumerate() {
+        let line_no = index + 1;
+        let line = line.trim();
+
+        if line.is_empty() {
+            continue;
+        }
+        if let Some(comment) = line.strip_prefix('#') {
+            if let Some(pattern_name) = comment.strip_prefix('N') {
+                name = Some(pattern_name.trim().to_string());
+            }
+            continue;
+        }
+        if !header_seen && line.starts_with('x') {
+            header_seen = true;
+            for field in line.split(',') {
+                let (key, value) = field.split_once('=').ok_or_else(|| {
+                    ParseError::new(line_no, format!("malformed header field `{}`", field.trim()))
+                })?;
+                match key.trim() {
+                    "x" | "y" => {
+                        value.trim().parse::<usize>().map_err(|_| {
+                            ParseError::new(line_no, format!("invalid size `{}`", value.trim()))
+                        })?;
+                    }
+                    "rule" => rule = Some(value.trim().to_string()),
+                    _ => {}
+                }
+            }
+            continue;
+        }
+
+        for ch in line.chars() {
+            match ch {
+                '0'..='9' => {
+                    let digit = ch as isize - '0' as isize;
+                    let length = run
+                        .unwrap_or(0)
+                        .checked_mul(10)
+                        .and_then(|length| length.checked_add(digit))
+                        .filter(|&length| length <= MAX_RUN_LENGTH)
+                        .ok_or_else(|| {
+                            ParseError::new(
+                                line_no,
+                                format!("runs are at most {} cells long", MAX_RUN_LENGTH),
+                            )
+                        })?;
+                    run = Some(length);
+                }
+                'b' | '.' => col += run.take().unwrap_or(1),
+                'o' => {
+                    for _ in 0..run.take().unwrap_or(1) {
+                        grid.set_cell(col, row, true);
+                        col += 1;
+                    }
+                }
+                '$' => {
+                    row += run.take().unwrap_or(1);
+                    col = 0;
+                }
+                '!' => break 'lines,
+                c if c.is_whitespace() => {}
+                other => {
+                    return Err(ParseError::new(
+                        line_no,
+                        format!("unexpected character `{}`", other),
+                    ));
+                }
+            }
+        }
+    }
+
+    Ok(PatternData { name, rule, grid })
+}
+
+/// Parses a pattern in plaintext (`.cells`) format, where `O` or `*` marks a live cell.
+pub fn parse_plaintext(text: &str) -> Result<PatternData, ParseError> {
+    let mut name = None;
+    let mut grid = Grid::new();
+    let mut row = 0isize;
+
+    for (index, line) in text.lines().enumerate() {
+        if let Some(comment) = line.strip_prefix('!') {
+            if let Some(pattern_name) = comment.strip_prefix("Name:") {
+                name = Some(pattern_name.trim().to_string());
+            }
+            continue;
+        }
+        for (col, ch) in line.trim_end().chars().enumerate() {
+            match ch {
+                'O' | '*' => grid.set_cell(col as isize, row, true),
+                '.' => {}
+                other => {
+                    return Err(ParseError::new(
+                        index + 1,
+                        format!("unexpected character `{}`", other),
+                    ));
+                }
+            }
+        }
+        row += 1;
+    }
+
+    Ok(PatternData {
+        name,
+        rule: None,
+        grid,
+    })
+}
+
+const RLE_LINE_LENGTH: usize = 70;
+
+/// Encodes the live cells of `grid` as RLE, with the pattern's top left corner at the origin.
+pub fn write_rle(grid: &Grid, rule: &Rule) -> String {
+    let Some((min, max)) = grid.bounds() else {
+        return format!("x = 0, y = 0, rule = {}\n!\n", rule);
+    };
+
+    let mut runs: Vec<(isize, char)> = Vec::new();
+    let mut push = |count: isize, tag: char| match runs.last_mut() {
+        Some((last_count, last_tag)) if *last_tag == tag => *last_count += count,
+        _ => runs.push((count, tag)),
+    };
+    for row in min.y..=max.y {
+        if row > min.y {
+            push(1, '$');
+        }
+        let mut dead = 0;
+        for col in min.x..=max.x {
+            if grid.get_cell(col, row) {
+                if dead > 0 {
+                    push(dead, 'b');
+                    dead = 0;
+                }
+                push(1, 'o');
+            } else {
+                dead += 1;
+            }
+        }
+    }
+
+    let mut out = format!(
+        "x = {}, y = {}, rule = {}\n",
+        max.x - min.x + 1,
+        max.y - min.y + 1,
+        rule
+    );
+    let mut line_length = 0;
+    let tokens = runs
+        .into_iter()
+        .map(|(count, tag)| match count {
+            1 => tag.to_string(),
+            _ => format!("{}{}", count, tag),
+        })
+        .chain(std::iter::once("!".to_string()));
+    for token in tokens {
+        if line_length + token.len() > RLE_LINE_LENGTH {
+            out.push('\n');
+            line_length = 0;
+        }
+        line_length += token.len();
+        out.push_str(&token);
+    }
+    out.push('\n');
+    out
+}
+
+/// Encodes the live cells of `grid` in plaintext format.
+pub fn write_plaintext(grid: &Grid) -> String {
+    let mut out = String::new();
+    if let Some((min, max)) = grid.bounds() {
+        for row in min.y..=max.y {
+            let line: String = (min.x..=max.x)
+                .map(|col| if grid.get_cell(col, row) { 'O' } else { '.' })
+                .collect();
+            out.push_str(line.trim_end_matches('.'));
+            out.push('\n');
+        }
+    }
+    out
+}
+
+#[derive(Debug)]
+pub enum PatternFileError {
+    Io(io::Error),
+    Parse(ParseError),
+}
+
+impl fmt::Display for PatternFileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PatternFileError::Io(e) => write!(f, "{}", e),
+            PatternFileError::Parse(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for PatternFileError {}
+
+fn is_plaintext(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("cells") || ext.eq_ignore_ascii_case("txt"))
+}
+
+/// Reads a pattern file, picking the format from its extension and defaulting to RLE.
+pub fn load_pattern(path: &Path) -> Result<PatternData, PatternFileError> {
+    let text = fs::read_to_string(path).map_err(PatternFileError::Io)?;
+    let parsed = if is_plaintext(path) {
+        parse_plaintext(&text)
+    } else {
+        parse_rle(&text)
+    };
+    parsed.map_err(PatternFileError::Parse)
+}
+
+/// Writes `grid` to a pattern file, picking the format from its extension and defaulting to RLE.
+pub fn save_pattern(path: &Path, grid: &Grid, rule: &Rule) -> io::Result<()> {
+    let text = if is_plaintext(path) {
+        write_plaintext(grid)
+    } else {
+        write_rle(grid, rule)
+    };
+    fs::write(path, text)
+}
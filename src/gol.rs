@@ -1,137 +1,459 @@
+use crate::history::History;
+use crate::rule::Rule;
 use rayon::iter::ParallelIterator;
 use rayon::prelude::{IntoParallelIterator, IntoParallelRefIterator};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
 
+/// A cell position: `x` is the column, growing to the right, and `y` is the row, growing down.
 #[derive(Debug, Clone, PartialEq, Eq, Copy, Hash)]
 pub struct Vec2Isize {
-    pub(crate) x: isize,
-    pub(crate) y: isize,
+    pub x: isize,
+    pub y: isize,
 }
 
 impl Vec2Isize {
-    fn new(x: isize, y: isize) -> Self {
+    pub fn new(x: isize, y: isize) -> Self {
         Self { x, y }
     }
 }
 
+/// Source of `Grid::version` values, shared by every grid so versions never repeat.
+static NEXT_VERSION: AtomicU64 = AtomicU64::new(1);
+
+fn next_version() -> u64 {
+    NEXT_VERSION.fetch_add(1, Ordering::Relaxed)
+}
+
+/// Cells per side of a tile, as a power of two so tile coordinates are a shift away.
+pub const TILE_SHIFT: u32 = 6;
+pub const TILE_SIZE: usize = 1 << TILE_SHIFT;
+const TILE_MASK: isize = TILE_SIZE as isize - 1;
+
+/// A `TILE_SIZE` square of cells, one bit per cell and one word per row.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Tile {
+    rows: [u64; TILE_SIZE],
+}
+
+impl Tile {
+    fn is_empty(&self) -> bool {
+        self.rows.iter().all(|row| *row == 0)
+    }
+
+    /// Live cells of the tile at tile coordinate `origin`.
+    fn cells(&self, origin: Vec2Isize) -> impl Iterator<Item = Vec2Isize> + '_ {
+        let (base_x, base_y) = (origin.x << TILE_SHIFT, origin.y << TILE_SHIFT);
+        self.rows.iter().enumerate().flat_map(move |(row, &bits)| {
+            SetBits(bits).map(move |col| Vec2Isize::new(base_x + col as isize, base_y + row as isize))
+        })
+    }
+}
+
+impl Default for Tile {
+    fn default() -> Self {
+        Tile {
+            rows: [0; TILE_SIZE],
+        }
+    }
+}
+
+/// Indices of the set bits in a word, lowest first.
+struct SetBits(u64);
+
+impl Iterator for SetBits {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<u32> {
+        if self.0 == 0 {
+            return None;
+        }
+        let index = self.0.trailing_zeros();
+        self.0 &= self.0 - 1;
+        Some(index)
+    }
+}
+
+fn tile_of(x: isize, y: isize) -> (Vec2Isize, usize, u64) {
+    (
+        Vec2Isize::new(x >> TILE_SHIFT, y >> TILE_SHIFT),
+        (y & TILE_MASK) as usize,
+        1 << (x & TILE_MASK),
+    )
+}
+
+/// Live cells, stored as bitmap tiles keyed by tile coordinate so region queries only visit
+/// the tiles they overlap. Tiles are dropped as soon as they empty.
 #[derive(Debug, Clone)]
 pub struct Grid {
-    pub grid: HashSet<Vec2Isize>,
+    tiles: HashMap<Vec2Isize, Tile>,
+    population: usize,
+    version: u64,
 }
 
 impl Grid {
     pub fn new() -> Grid {
         Default::default()
     }
+
+    /// Changes whenever the live cells do, while clones keep the version of their original.
+    /// Lets callers cache data derived from a grid without comparing cells.
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
     pub fn get_cell(&self, x: isize, y: isize) -> bool {
-        self.grid.contains(&Vec2Isize::new(x, y))
+        let (tile, row, bit) = tile_of(x, y);
+        self.tiles
+            .get(&tile)
+            .is_some_and(|tile| tile.rows[row] & bit != 0)
     }
 
     pub fn set_cell(&mut self, x: isize, y: isize, state: bool) {
+        let (tile_pos, row, bit) = tile_of(x, y);
 
-        let gotten_cell = self.get_cell(x, y);
-
-        if gotten_cell {
-            if state {
-                return;
-            } else {
-                self.grid.remove(&Vec2Isize::new(x, y));
+        if state {
+            let tile = self.tiles.entry(tile_pos).or_default();
+            if tile.rows[row] & bit == 0 {
+                tile.rows[row] |= bit;
+                self.population += 1;
+                self.version = next_version();
             }
-        } else {
-            if state {
-                self.grid.insert(Vec2Isize::new(x, y));
-            } else {
-                return;
+        } else if let Some(tile) = self.tiles.get_mut(&tile_pos)
+            && tile.rows[row] & bit != 0
+        {
+            tile.rows[row] &= !bit;
+            self.population -= 1;
+            self.version = next_version();
+            if tile.is_empty() {
+                self.tiles.remove(&tile_pos);
             }
         }
     }
 
     pub fn clear_all(&mut self) {
-        self.grid = HashSet::new();
+        self.tiles = HashMap::new();
+        self.population = 0;
+        self.version = next_version();
+    }
+
+    /// The tiles holding live cells, keyed by tile coordinate (cell coordinate shifted right by
+    /// `TILE_SHIFT`). Bit `x` of row `y` is the cell `(x, y)` from the tile's top-left corner.
+    pub fn tiles(&self) -> impl Iterator<Item = (Vec2Isize, &[u64; TILE_SIZE])> + '_ {
+        self.tiles.iter().map(|(origin, tile)| (*origin, &tile.rows))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Vec2Isize> + '_ {
+        self.tiles.iter().flat_map(|(origin, tile)| tile.cells(*origin))
+    }
+
+    pub fn population(&self) -> usize {
+        self.population
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.population == 0
+    }
+
+    /// Iterates the live cells inside the inclusive rectangle `min..=max`, visiting only the
+    /// tiles that overlap it.
+    pub fn cells_in(
+        &self,
+        min: Vec2Isize,
+        max: Vec2Isize,
+    ) -> Box<dyn Iterator<Item = Vec2Isize> + '_> {
+        if max.x < min.x || max.y < min.y {
+            return Box::new(std::iter::empty());
+        }
+        let (tile_min, _, _) = tile_of(min.x, min.y);
+        let (tile_max, _, _) = tile_of(max.x, max.y);
+        let in_rect = move |cell: &Vec2Isize| {
+            (min.x..=max.x).contains(&cell.x) && (min.y..=max.y).contains(&cell.y)
+        };
+
+        // Probe each tile position for small rectangles, otherwise filter the stored tiles.
+        let tile_area =
+            (tile_max.x - tile_min.x + 1).saturating_mul(tile_max.y - tile_min.y + 1) as usize;
+        if tile_area <= self.tiles.len() {
+            Box::new(
+                (tile_min.x..=tile_max.x)
+                    .flat_map(move |x| (tile_min.y..=tile_max.y).map(move |y| Vec2Isize::new(x, y)))
+                    .filter_map(|origin| Some((origin, self.tiles.get(&origin)?)))
+                    .flat_map(|(origin, tile)| tile.cells(origin))
+                    .filter(in_rect),
+            )
+        } else {
+            Box::new(
+                self.tiles
+                    .iter()
+                    .filter(move |(origin, _)| {
+                        (tile_min.x..=tile_max.x).contains(&origin.x)
+                            && (tile_min.y..=tile_max.y).contains(&origin.y)
+                    })
+                    .flat_map(|(origin, tile)| tile.cells(*origin))
+                    .filter(in_rect),
+            )
+        }
     }
-    pub fn get_grid(&mut self) -> HashSet<Vec2Isize> {
-        self.grid.clone()
+
+    /// Number of live cells inside the inclusive rectangle `min..=max`.
+    pub fn population_in(&self, min: Vec2Isize, max: Vec2Isize) -> usize {
+        self.cells_in(min, max).count()
+    }
+
+    /// Returns the inclusive `(min, max)` corners of the live cells, or `None` if the grid is empty.
+    pub fn bounds(&self) -> Option<(Vec2Isize, Vec2Isize)> {
+        let mut cells = self.iter();
+        let first = cells.next()?;
+        Some(cells.fold((first, first), |(min, max), cell| {
+            (
+                Vec2Isize::new(min.x.min(cell.x), min.y.min(cell.y)),
+                Vec2Isize::new(max.x.max(cell.x), max.y.max(cell.y)),
+            )
+        }))
+    }
+
+    /// Sets every live cell of `pattern` in this grid, shifted by `(x, y)`.
+    pub fn paste(&mut self, pattern: &Grid, x: isize, y: isize) {
+        for cell in pattern.iter() {
+            self.set_cell(cell.x + x, cell.y + y, true);
+        }
+    }
+}
+
+impl PartialEq for Grid {
+    fn eq(&self, other: &Self) -> bool {
+        self.tiles == other.tiles
+    }
+}
+
+impl Eq for Grid {}
+
+impl FromIterator<Vec2Isize> for Grid {
+    fn from_iter<I: IntoIterator<Item = Vec2Isize>>(iter: I) -> Self {
+        let mut grid = Grid::new();
+        for cell in iter {
+            grid.set_cell(cell.x, cell.y, true);
+        }
+        grid
     }
 }
 
 impl Default for Grid {
     fn default() -> Self {
-        let grid: HashSet<Vec2Isize> = HashSet::new();
-
         Grid {
-            grid,
+            tiles: HashMap::new(),
+            population: 0,
+            version: next_version(),
+        }
+    }
+}
+
+/// The cell itself followed by its eight neighbours.
+fn neighbourhood(cell: Vec2Isize) -> [Vec2Isize; 9] {
+    let (x, y) = (cell.x, cell.y);
+    [
+        Vec2Isize::new(x, y),
+        Vec2Isize::new(x - 1, y - 1), Vec2Isize::new(x - 1, y), Vec2Isize::new(x - 1, y + 1),
+        Vec2Isize::new(x, y - 1), Vec2Isize::new(x, y + 1),
+        Vec2Isize::new(x + 1, y - 1), Vec2Isize::new(x + 1, y), Vec2Isize::new(x + 1, y + 1),
+    ]
+}
+
+fn next_state(alive: &Grid, rule: &Rule, cell: Vec2Isize) -> bool {
+    let [own, neighbours @ ..] = neighbourhood(cell);
+    let count = neighbours.iter().filter(|n| alive.get_cell(n.x, n.y)).count() as u8;
+    rule.next_state(alive.get_cell(own.x, own.y), count)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Engine {
+    /// Spreads neighbour counting over the rayon thread pool.
+    #[default]
+    Parallel,
+    /// Runs on the calling thread, useful for small patterns and reproducible timings.
+    Serial,
+}
+
+impl FromStr for Engine {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "parallel" => Ok(Engine::Parallel),
+            "serial" => Ok(Engine::Serial),
+            other => Err(format!(
+                "unknown engine `{}`, expected `parallel` or `serial`",
+                other
+            )),
         }
     }
 }
 
 #[derive(Debug, Clone)]
 pub struct GOL {
-    pub grid: Grid,
+    /// Shared with whatever else holds this generation, such as a simulation thread, so handing
+    /// it around never copies the cells. Edit it through `set_cell` or `grid_mut`.
+    pub grid: Arc<Grid>,
     pub paused: bool,
+    pub rule: Rule,
+    pub engine: Engine,
+    /// Generations stepped since the simulation started.
+    pub generation: u64,
+    /// Per-cell metadata, only kept up to date while something needs it.
+    pub history: Option<History>,
 }
 
 impl GOL {
     pub fn new(grid: Grid) -> GOL {
         GOL {
-            grid,
+            grid: Arc::new(grid),
             paused: true,
+            rule: Rule::default(),
+            engine: Engine::default(),
+            generation: 0,
+            history: None,
+        }
+    }
+
+    /// The grid, for editing. Copies it first if it's shared.
+    pub fn grid_mut(&mut self) -> &mut Grid {
+        Arc::make_mut(&mut self.grid)
+    }
+
+    /// Sets a cell, leaving the grid alone, and so uncopied, if the cell is already in `state`.
+    pub fn set_cell(&mut self, x: isize, y: isize, state: bool) {
+        if self.grid.get_cell(x, y) != state {
+            self.grid_mut().set_cell(x, y, state);
+        }
+    }
+
+    /// Empties the grid, without copying it first.
+    pub fn clear(&mut self) {
+        self.grid = Arc::new(Grid::new());
+    }
+
+    pub fn step(&mut self) {
+        self.step_by(1);
+    }
+
+    pub fn step_by(&mut self, generations: u64) {
+        let next = GOL::update_by(&self.grid, &self.rule, self.engine, generations);
+        self.advance_by(Arc::new(next), generations);
+    }
+
+    /// Replaces the grid with the generation after it, which may have been computed elsewhere
+    /// with `update_from`, and records the change in the history.
+    pub fn advance_to(&mut self, next: Grid) {
+        self.advance_by(Arc::new(next), 1);
+    }
+
+    /// Replaces the grid with the one `generations` later, as computed by `update_by`. The
+    /// history records the jump as a single change.
+    pub fn advance_by(&mut self, next: Arc<Grid>, generations: u64) {
+        if let Some(history) = &mut self.history {
+            history.advance(&self.grid, &next);
         }
+        self.grid = next;
+        self.generation += generations;
     }
 
-    pub(crate) fn update_from(grid: &Grid) -> Grid {
-        rayon::ThreadPoolBuilder::new()
-            .num_threads(rayon::current_num_threads())
-            .build()
-            .ok();
+    /// Starts or stops keeping per-cell history. Starting treats every live cell as newborn.
+    pub fn track_history(&mut self, enabled: bool) {
+        match (enabled, &self.history) {
+            (true, None) => self.history = Some(History::new(&self.grid)),
+            (false, Some(_)) => self.history = None,
+            _ => {}
+        }
+    }
+
+    /// The grid `generations` generations after `grid`, stepping at least once.
+    pub fn update_by(grid: &Grid, rule: &Rule, engine: Engine, generations: u64) -> Grid {
+        let mut next = GOL::update_from(grid, rule, engine);
+        for _ in 1..generations {
+            next = GOL::update_from(&next, rule, engine);
+        }
+        next
+    }
 
-        let current = &grid.grid;
-        if current.is_empty() {
-            return Grid { grid: HashSet::new() };
+    pub fn update_from(grid: &Grid, rule: &Rule, engine: Engine) -> Grid {
+        if grid.is_empty() {
+            return Grid::new();
         }
+        let alive: Vec<Vec2Isize> = grid.iter().collect();
+
+        let next_cells: Vec<Vec2Isize> = match engine {
+            Engine::Parallel => {
+                let all_candidates: HashSet<Vec2Isize> = alive
+                    .par_iter()
+                    .flat_map(|candidate| neighbourhood(*candidate).into_par_iter())
+                    .collect();
 
-        let alive: HashSet<Vec2Isize> = current.par_iter()
-            .map(|c| Vec2Isize::new(c.x, c.y))
-            .collect();
-
-        let all_candidates: HashSet<Vec2Isize> = alive
-            .par_iter()
-            .flat_map(|candidate| {
-                let (x, y) = (candidate.x, candidate.y);
-                [
-                    Vec2Isize::new(x - 1, y - 1), Vec2Isize::new(x - 1, y), Vec2Isize::new(x - 1, y + 1),
-                    Vec2Isize::new(x, y - 1), Vec2Isize::new(x, y), Vec2Isize::new(x, y + 1),
-                    Vec2Isize::new(x + 1, y - 1), Vec2Isize::new(x + 1, y), Vec2Isize::new(x + 1, y + 1),
-                ].into_par_iter()
-            })
-            .collect();
-
-        let next_cells: HashSet<Vec2Isize> = all_candidates
-            .par_iter()
-            .filter_map(|next_cell| {
-                let (x, y) = (next_cell.x, next_cell.y);
-                let mut count = 0u8;
-
-                if alive.contains(&Vec2Isize::new(x - 1, y - 1)) { count += 1; }
-                if alive.contains(&Vec2Isize::new(x - 1, y)) { count += 1; }
-                if alive.contains(&Vec2Isize::new(x - 1, y + 1)) { count += 1; }
-                if alive.contains(&Vec2Isize::new(x, y - 1)) { count += 1; }
-                if alive.contains(&Vec2Isize::new(x, y + 1)) { count += 1; }
-                if alive.contains(&Vec2Isize::new(x + 1, y - 1)) { count += 1; }
-                if alive.contains(&Vec2Isize::new(x + 1, y)) { count += 1; }
-                if alive.contains(&Vec2Isize::new(x + 1, y + 1)) { count += 1; }
-
-                let is_alive = alive.contains(&Vec2Isize::new(x, y));
-                match (is_alive, count) {
-                    (true, 2) | (_, 3) => Some(Vec2Isize::new(x, y)),
-                    _ => None,
-                }
-            })
-            .collect();
-
-        Grid { grid: next_cells }
+                all_candidates
+                    .par_iter()
+                    .filter(|cell| next_state(grid, rule, **cell))
+                    .copied()
+                    .collect()
+            }
+            Engine::Serial => {
+                let all_candidates: HashSet<Vec2Isize> = alive
+                    .iter()
+                    .flat_map(|candidate| neighbourhood(*candidate))
+                    .collect();
+
+                all_candidates
+                    .into_iter()
+                    .filter(|cell| next_state(grid, rule, *cell))
+                    .collect()
+            }
+        };
+
+        next_cells.into_iter().collect()
     }
 
     pub fn pause(&mut self) {
         self.paused = !self.paused;
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tile_of_rounds_towards_negative_infinity() {
+        assert_eq!(tile_of(0, 0), (Vec2Isize::new(0, 0), 0, 1));
+        assert_eq!(tile_of(63, 63), (Vec2Isize::new(0, 0), 63, 1 << 63));
+        assert_eq!(tile_of(64, 64), (Vec2Isize::new(1, 1), 0, 1));
+        assert_eq!(tile_of(-1, -1), (Vec2Isize::new(-1, -1), 63, 1 << 63));
+        assert_eq!(tile_of(-64, -64), (Vec2Isize::new(-1, -1), 0, 1));
+        assert_eq!(tile_of(-65, 5), (Vec2Isize::new(-2, 0), 5, 1 << 63));
+    }
+
+    #[test]
+    fn tiles_are_dropped_when_they_empty() {
+        let mut grid = Grid::new();
+        grid.set_cell(-1, -1, true);
+        grid.set_cell(-2, -1, true);
+        grid.set_cell(0, 0, true);
+        assert_eq!(grid.tiles.len(), 2);
+        grid.set_cell(-1, -1, false);
+        assert_eq!(grid.tiles.len(), 2);
+        grid.set_cell(-2, -1, false);
+        assert_eq!(grid.tiles.len(), 1);
+        assert!(!grid.tiles.contains_key(&Vec2Isize::new(-1, -1)));
+        grid.set_cell(0, 0, false);
+        assert!(grid.tiles.is_empty());
+    }
+
+    #[test]
+    fn tile_cells_are_placed_by_tile_origin() {
+        let mut tile = Tile::default();
+        tile.rows[0] = 1;
+        tile.rows[63] = 1 << 63;
+        let cells: Vec<_> = tile.cells(Vec2Isize::new(-1, 2)).collect();
+        assert_eq!(cells, [Vec2Isize::new(-64, 128), Vec2Isize::new(-1, 191)]);
+    }
+}
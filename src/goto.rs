@@ -0,0 +1,70 @@
+use sdl3::event::Event;
+use sdl3::keyboard::Keycode;
+
+pub enum GotoAction {
+    None,
+    Close,
+    /// Centre the view on this cell, as `(x, y)`.
+    Go(isize, isize),
+}
+
+/// Text prompt asking for a cell to centre the view on.
+pub struct GotoDialog {
+    pub open: bool,
+    pub input: String,
+    /// Why the last submitted input was rejected.
+    pub error: Option<String>,
+}
+
+/// Parses `x, y` or `x y` cell coordinates.
+fn parse_coordinates(input: &str) -> Result<(isize, isize), String> {
+    let parts: Vec<&str> = input
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|part| !part.is_empty())
+        .collect();
+    let [x, y] = parts[..] else {
+        return Err("expected two coordinates, e.g. `120, -45`".to_string());
+    };
+    let parse = |value: &str| {
+        value
+            .parse::<isize>()
+            .map_err(|_| format!("`{}` is not a whole number", value))
+    };
+    Ok((parse(x)?, parse(y)?))
+}
+
+impl GotoDialog {
+    pub fn new() -> GotoDialog {
+        GotoDialog {
+            open: false,
+            input: String::new(),
+            error: None,
+        }
+    }
+
+    pub fn handle_event(&mut self, event: &Event) -> GotoAction {
+        match event {
+            Event::TextInput { text, .. } => {
+                self.input.push_str(text);
+                self.error = None;
+            }
+            Event::KeyDown {
+                keycode: Some(keycode),
+                ..
+            } => match *keycode {
+                Keycode::Escape => return GotoAction::Close,
+                Keycode::Backspace => {
+                    self.input.pop();
+                    self.error = None;
+                }
+                Keycode::Return => match parse_coordinates(&self.input) {
+                    Ok((x, y)) => return GotoAction::Go(x, y),
+                    Err(e) => self.error = Some(e),
+                },
+                _ => {}
+            },
+            _ => {}
+        }
+        GotoAction::None
+    }
+}
@@ -0,0 +1,229 @@
+use crate::formats::{load_pattern, save_pattern};
+use crate::gol::{Engine, Grid, Vec2Isize, GOL};
+use crate::rule::Rule;
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+/// How many past generations are remembered when looking for a repeating state.
+const MAX_PERIOD: u64 = 64;
+
+/// Generations `--until-stable` runs for at most when `--generations` isn't given, so patterns
+/// that never settle, such as guns and puffers, still finish.
+pub const DEFAULT_STABLE_LIMIT: u64 = 10_000;
+
+pub struct HeadlessOptions {
+    pub pattern: PathBuf,
+    pub rule: Option<Rule>,
+    pub engine: Engine,
+    pub generations: Option<u64>,
+    pub until_stable: bool,
+    pub output: Option<PathBuf>,
+}
+
+pub fn parse_args(args: &[String]) -> Result<HeadlessOptions, String> {
+    let mut pattern = None;
+    let mut rule = None;
+    let mut engine = Engine::default();
+    let mut generations = None;
+    let mut until_stable = false;
+    let mut output = None;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| format!("`{}` expects a value", arg))
+        };
+        match arg.as_str() {
+            "--rule" => rule = Some(value()?.parse::<Rule>().map_err(|e| e.to_string())?),
+            "--engine" => engine = value()?.parse()?,
+            "--generations" => {
+                let count = value()?;
+                generations = Some(
+                    count
+                        .parse::<u64>()
+                        .map_err(|_| format!("invalid generation count `{}`", count))?,
+                );
+            }
+            "--until-stable" => until_stable = true,
+            "--output" => output = Some(PathBuf::from(value()?)),
+            flag if flag.starts_with("--") => return Err(format!("unknown option `{}`", flag)),
+            path if pattern.is_none() => pattern = Some(PathBuf::from(path)),
+            extra => return Err(format!("unexpected argument `{}`", extra)),
+        }
+    }
+
+    let pattern = pattern.ok_or("missing pattern file")?;
+    let generations = match (generations, until_stable) {
+        (None, false) => return Err("pass --generations, --until-stable or both".to_string()),
+        (None, true) => Some(DEFAULT_STABLE_LIMIT),
+        (generations, _) => generations,
+    };
+
+    Ok(HeadlessOptions {
+        pattern,
+        rule,
+        engine,
+        generations,
+        until_stable,
+        output,
+    })
+}
+
+/// A state the pattern keeps coming back to, possibly shifted, as spaceships do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    pub period: u64,
+    /// First generation of the cycle.
+    pub start: u64,
+    /// How far the pattern moves each period.
+    pub offset: Vec2Isize,
+}
+
+pub struct Outcome {
+    pub grid: Grid,
+    pub generation: u64,
+    /// The cycle the pattern settled into, if one was found.
+    pub cycle: Option<Cycle>,
+}
+
+/// A past generation, moved so its bounding box starts at the origin so that shifted copies of
+/// the same state compare equal.
+struct Shape {
+    /// Order independent hash of the normalised cells, to rule out most candidates cheaply.
+    hash: u64,
+    cells: Grid,
+    /// Where the bounding box started before normalising.
+    corner: Vec2Isize,
+    generation: u64,
+}
+
+impl Shape {
+    fn of(grid: &Grid, generation: u64) -> Shape {
+        let corner = grid.bounds().map_or(Vec2Isize::new(0, 0), |(min, _)| min);
+        let cells: Grid = grid
+            .iter()
+            .map(|cell| Vec2Isize::new(cell.x - corner.x, cell.y - corner.y))
+            .collect();
+        let hash = cells.iter().fold(0u64, |acc, cell| {
+            let mut z = (cell.x as u64)
+                .rotate_left(32)
+                .wrapping_add(cell.y as u64)
+                .wrapping_add(0x9e3779b97f4a7c15);
+            z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+            acc.wrapping_add(z ^ (z >> 31))
+        });
+        Shape {
+            hash,
+            cells,
+            corner,
+            generation,
+        }
+    }
+}
+
+/// Advances `grid` until `generations` have passed or, with `until_stable`, the pattern
+/// repeats a state from the last `MAX_PERIOD` generations, in place or shifted. Without a
+/// generation limit, `until_stable` runs until a repeat however long that takes.
+pub fn simulate(
+    grid: Grid,
+    rule: &Rule,
+    engine: Engine,
+    generations: Option<u64>,
+    until_stable: bool,
+) -> Outcome {
+    let mut grid = grid;
+    let mut generation = 0;
+    let mut history: VecDeque<Shape> = VecDeque::new();
+
+    loop {
+        if until_stable {
+            let shape = Shape::of(&grid, generation);
+            // Hashes can collide, so only identical cells count as a repeat.
+            let repeat = history
+                .iter()
+                .find(|past| past.hash == shape.hash && past.cells == shape.cells);
+            if let Some(past) = repeat {
+                let cycle = Cycle {
+                    period: generation - past.generation,
+                    start: past.generation,
+                    offset: Vec2Isize::new(
+                        shape.corner.x - past.corner.x,
+                        shape.corner.y - past.corner.y,
+                    ),
+                };
+                return Outcome {
+                    grid,
+                    generation,
+                    cycle: Some(cycle),
+                };
+            }
+            history.push_back(shape);
+            if history.len() as u64 > MAX_PERIOD {
+                history.pop_front();
+            }
+        }
+        if generations.is_some_and(|limit| generation >= limit) {
+            return Outcome {
+                grid,
+                generation,
+                cycle: None,
+            };
+        }
+
+        grid = GOL::update_from(&grid, rule, engine);
+        generation += 1;
+    }
+}
+
+/// What a headless run ended with.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Report {
+    /// The rule the pattern ran under, from the options, the pattern file or the default.
+    pub rule: Rule,
+    pub generation: u64,
+    pub population: usize,
+    /// Inclusive `(min, max)` corners of the final pattern, or `None` if it died out.
+    pub bounds: Option<(Vec2Isize, Vec2Isize)>,
+    /// The cycle the pattern settled into, if `until_stable` found one.
+    pub cycle: Option<Cycle>,
+    pub elapsed: Duration,
+}
+
+/// Loads the pattern, runs it and writes the result to the output file if there is one.
+pub fn run(options: &HeadlessOptions) -> Result<Report, String> {
+    let pattern = load_pattern(&options.pattern)
+        .map_err(|e| format!("couldn't load {}: {}", options.pattern.display(), e))?;
+
+    let rule = match (options.rule, &pattern.rule) {
+        (Some(rule), _) => rule,
+        (None, Some(rule)) => rule.parse::<Rule>().map_err(|e| e.to_string())?,
+        (None, None) => Rule::default(),
+    };
+
+    let start = Instant::now();
+    let outcome = simulate(
+        pattern.grid,
+        &rule,
+        options.engine,
+        options.generations,
+        options.until_stable,
+    );
+    let elapsed = start.elapsed();
+
+    if let Some(output) = &options.output {
+        save_pattern(output, &outcome.grid, &rule)
+            .map_err(|e| format!("couldn't write {}: {}", output.display(), e))?;
+    }
+
+    Ok(Report {
+        rule,
+        generation: outcome.generation,
+        population: outcome.grid.population(),
+        bounds: outcome.grid.bounds(),
+        cycle: outcome.cycle,
+        elapsed,
+    })
+}
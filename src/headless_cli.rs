@@ -0,0 +1,63 @@
+//! The `gol-headless` command line, shared by the `gol-headless` binary and the viewer's `run`
+//! subcommand.
+
+use rust_sdl_gol::gol::Vec2Isize;
+use rust_sdl_gol::headless::{parse_args, run, Report};
+
+const USAGE: &str = "<pattern> [--rule B3/S23] [--engine parallel|serial] [--generations N] \
+[--until-stable] [--output FILE]";
+
+/// Parses `args` and runs the simulation, returning the process exit code. `command` is shown
+/// in the usage message.
+pub fn main(command: &str, args: &[String]) -> i32 {
+    if args.iter().any(|arg| arg == "-h" || arg == "--help") {
+        println!("usage: {} {}", command, USAGE);
+        return 0;
+    }
+
+    let result = parse_args(args)
+        .map_err(|e| format!("{}\nusage: {} {}", e, command, USAGE))
+        .and_then(|options| Ok((run(&options)?, options.until_stable)));
+
+    match result {
+        Ok((report, until_stable)) => {
+            print_report(&report, until_stable);
+            0
+        }
+        Err(e) => {
+            eprintln!("error: {}", e);
+            1
+        }
+    }
+}
+
+fn print_report(report: &Report, until_stable: bool) {
+    println!("rule: {}", report.rule);
+    println!("generation: {}", report.generation);
+    println!("population: {}", report.population);
+    match report.bounds {
+        Some((min, max)) => println!(
+            "bounding box: {}x{} at ({}, {})",
+            max.x - min.x + 1,
+            max.y - min.y + 1,
+            min.x,
+            min.y
+        ),
+        None => println!("bounding box: empty"),
+    }
+    match report.cycle {
+        Some(cycle) if cycle.offset == Vec2Isize::new(0, 0) => println!(
+            "stable: period {} from generation {}",
+            cycle.period, cycle.start
+        ),
+        Some(cycle) => println!(
+            "stable: period {} from generation {}, moving ({}, {}) each period",
+            cycle.period, cycle.start, cycle.offset.x, cycle.offset.y
+        ),
+        None if until_stable => {
+            println!("stable: not within {} generations", report.generation)
+        }
+        None => {}
+    }
+    println!("elapsed: {:.3}s", report.elapsed.as_secs_f64());
+}
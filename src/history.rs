@@ -0,0 +1,87 @@
+//! Per-cell metadata carried alongside a simulation: how long cells have been alive, how often
+//! they changed recently and how long ago they died.
+
+use crate::gol::{Grid, Vec2Isize};
+use std::collections::HashMap;
+
+/// Generations of toggles remembered per cell, one bit each.
+pub const HEAT_WINDOW: u32 = 64;
+
+/// Generations a dead cell stays in the trail.
+pub const TRAIL_LENGTH: u32 = 16;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct History {
+    /// Generations each live cell has been alive, 1 for cells born in the latest step.
+    age: HashMap<Vec2Isize, u32>,
+    /// Bit `n` is set if the cell was born or died `n` generations ago.
+    toggles: HashMap<Vec2Isize, u64>,
+    /// Generations since each recently dead cell died, 1 for cells that died in the latest step.
+    trails: HashMap<Vec2Isize, u32>,
+}
+
+impl History {
+    /// Starts tracking `grid`, treating every live cell as newly born.
+    pub fn new(grid: &Grid) -> Self {
+        History {
+            age: grid.iter().map(|cell| (cell, 1)).collect(),
+            toggles: HashMap::new(),
+            trails: HashMap::new(),
+        }
+    }
+
+    /// Records one generation going from `previous` to `next`.
+    pub fn advance(&mut self, previous: &Grid, next: &Grid) {
+        let mut age = HashMap::with_capacity(next.population());
+        for cell in next.iter() {
+            age.insert(cell, self.age.get(&cell).map_or(1, |age| age + 1));
+        }
+        self.age = age;
+
+        self.toggles.retain(|_, toggles| {
+            *toggles <<= 1;
+            *toggles != 0
+        });
+        self.trails.retain(|_, since| {
+            *since += 1;
+            *since <= TRAIL_LENGTH
+        });
+
+        for cell in next
+            .iter()
+            .filter(|cell| !previous.get_cell(cell.x, cell.y))
+        {
+            *self.toggles.entry(cell).or_insert(0) |= 1;
+            self.trails.remove(&cell);
+        }
+        for cell in previous
+            .iter()
+            .filter(|cell| !next.get_cell(cell.x, cell.y))
+        {
+            *self.toggles.entry(cell).or_insert(0) |= 1;
+            self.trails.insert(cell, 1);
+        }
+    }
+
+    /// Generations `cell` has been alive, or 0 if it isn't tracked as alive.
+    pub fn age(&self, cell: Vec2Isize) -> u32 {
+        self.age.get(&cell).copied().unwrap_or(0)
+    }
+
+    /// Whether the live `cell` was born in the latest generation rather than surviving into it.
+    /// Cells drawn by hand since the last step have no age yet and count as births.
+    pub fn is_birth(&self, cell: Vec2Isize) -> bool {
+        self.age(cell) <= 1
+    }
+
+    /// Fraction of the last `HEAT_WINDOW` generations in which `cell` was born or died.
+    pub fn heat(&self, cell: Vec2Isize) -> f32 {
+        let toggles = self.toggles.get(&cell).copied().unwrap_or(0);
+        toggles.count_ones() as f32 / HEAT_WINDOW as f32
+    }
+
+    /// Recently dead cells with the number of generations since they died.
+    pub fn trails(&self) -> impl Iterator<Item = (Vec2Isize, u32)> + '_ {
+        self.trails.iter().map(|(cell, since)| (*cell, *since))
+    }
+}
@@ -0,0 +1,11 @@
+//! Sparse, unbounded Game of Life simulation: grids, Life-like rules, stepping engines and
+//! pattern file formats. The SDL viewer in `main.rs` is built on top of this crate.
+
+pub mod camera;
+pub mod formats;
+pub mod gol;
+pub mod headless;
+pub mod history;
+pub mod patterns;
+pub mod rule;
+pub mod soup;
@@ -1,59 +1,172 @@
-mod gol;
+mod bitmap_font;
+mod bookmarks;
+mod browser;
+mod brush;
+mod cell_layer;
+mod cli;
+mod coloring;
+mod config;
+mod density;
+mod follow;
+mod font;
+mod goto;
+mod headless_cli;
+mod minimap;
+mod motion;
+mod pacing;
+mod random_fill;
 mod render;
+mod session;
+mod simulation;
+mod text;
+mod theme;
+mod tools;
 mod types;
 
-use crate::gol::*;
+use crate::bookmarks::{BookmarkAction, BookmarkDialog, Transition, View};
+use crate::browser::{BrowserAction, PatternBrowser};
+use crate::brush::{Brush, Stroke};
+use crate::cell_layer::CellLayer;
+use crate::config::{Action, BrushSettings, Config};
+use crate::density::DensityPyramid;
+use crate::follow::{Follow, FollowTarget};
+use crate::font::find_font;
+use crate::goto::{GotoAction, GotoDialog};
+use crate::minimap::Minimap;
+use crate::motion::{CameraMotion, KEY_ZOOM_STEP, WHEEL_ZOOM_STEP};
+use crate::pacing::{Pacing, Rate, RateMeter};
+use crate::random_fill::{RandomFillAction, RandomFillDialog};
 use crate::render::draw_frame;
+use crate::session::Session;
+use crate::simulation::Simulation;
+use crate::text::TextRenderer;
+use crate::tools::{Tool, Tools};
 use log::{info, warn};
+use rust_sdl_gol::camera::{Vector2, ViewState};
+use rust_sdl_gol::formats::load_pattern;
+use rust_sdl_gol::gol::*;
+use rust_sdl_gol::rule::Rule;
 use sdl3::event::Event;
-use sdl3::keyboard::Keycode;
+use sdl3::keyboard::Mod;
 use sdl3::mouse::MouseButton;
 use sdl3::pixels::Color;
-use sdl3::ttf::{Font, Sdl3TtfContext};
-use sdl3::{ttf, Error};
+use sdl3::ttf;
 use std::default::Default;
-use std::path::Path;
-use std::sync::mpsc::{self, TryRecvError};
+use std::path::PathBuf;
 use std::thread;
 use std::time::{Duration, Instant};
-use types::{Vector2, ViewState};
-use crate::types::{RenderCtx, UpdateResult};
-
-fn handle_font_error<'font>(e: Error, font_context: Sdl3TtfContext) -> Font<'font> {
-    warn!("Couldn't load font: {}", e);
-    if Path::exists("/usr/share/fonts/truetype/dejavu/DejaVuSans.ttf".as_ref()) {
-        return font_context
-            .load_font("/usr/share/fonts/truetype/dejavu/DejaVuSans.ttf", 32f32)
-            .unwrap();
+use crate::types::RenderCtx;
+
+/// Offset that centres `stamp` on the cell under the cursor.
+fn stamp_origin(stamp: &Grid, viewstate: &ViewState) -> Vec2Isize {
+    let cursor = viewstate.cursor_cell();
+    match stamp.bounds() {
+        Some((min, max)) => Vec2Isize::new(
+            cursor.x - (min.x + max.x) / 2,
+            cursor.y - (min.y + max.y) / 2,
+        ),
+        None => cursor,
     }
-    panic!("Couldn't load font");
 }
 
 fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().is_some_and(|arg| arg == "run") {
+        std::process::exit(headless_cli::main("rust_sdl_gol run", &args[1..]));
+    }
+
+    let options = cli::parse_args(args).unwrap_or_else(|e| {
+        eprintln!("error: {}\ntry `rust_sdl_gol --help`", e);
+        std::process::exit(2);
+    });
+
+    let config_path = Config::path();
+    let (config, save_config) = match config_path.as_deref().map(Config::load) {
+        Some(Ok(config)) => (config, true),
+        Some(Err(e)) => {
+            // Keep the broken file around for the user to fix rather than overwriting it.
+            warn!("Couldn't load config, using defaults: {}", e);
+            (Config::default(), false)
+        }
+        None => (Config::default(), false),
+    };
+    let keymap = config.keys.keymap();
+
+    let session_path = Session::path();
+    let (mut session, save_session) = match session_path.as_deref().map(Session::load) {
+        Some(Ok(session)) => (session, true),
+        Some(Err(e)) => {
+            warn!("Couldn't load session, starting a new one: {}", e);
+            (Session::default(), false)
+        }
+        None => (Session::default(), false),
+    };
+    let theme = match &options.theme {
+        Some(name) => config.theme_named(name).unwrap_or_else(|| {
+            eprintln!(
+                "error: unknown theme `{}`, expected one of: {}",
+                name,
+                config.theme_names().join(", ")
+            );
+            std::process::exit(2);
+        }),
+        None => config.theme(),
+    };
+
+    let (grid, pattern_rule) = match &options.pattern {
+        Some(path) => {
+            let pattern = load_pattern(path).unwrap_or_else(|e| {
+                eprintln!("error: couldn't load {}: {}", path.display(), e);
+                std::process::exit(1);
+            });
+            (pattern.grid, pattern.rule)
+        }
+        None => (Grid::new(), None),
+    };
+    let rule = match (options.rule, pattern_rule) {
+        (Some(rule), _) => rule,
+        (None, Some(rule)) => rule.parse::<Rule>().unwrap_or_else(|e| {
+            eprintln!("error: {}", e);
+            std::process::exit(1);
+        }),
+        (None, None) => Rule::default(),
+    };
+
+    if let Some(threads) = options.threads
+        && let Err(e) = rayon::ThreadPoolBuilder::new().num_threads(threads).build_global()
+    {
+        warn!("Couldn't configure rayon thread pool: {}", e);
+    }
     info!("rayon using: {} threads", rayon::current_num_threads());
 
     let sdl_context = sdl3::init().unwrap();
     let video_subsystem = sdl_context.video().unwrap();
     info!("initialized SDL3");
 
-    let window = video_subsystem
-        .window("Game of Life", 800, 600)
-        .position_centered()
-        .resizable()
-        .build()
-        .unwrap();
+    let (window_width, window_height) = options.window_size;
+    let mut window_builder = video_subsystem.window("Game of Life", window_width, window_height);
+    window_builder.position_centered().resizable();
+    if options.fullscreen {
+        window_builder.fullscreen();
+    }
+    let window = window_builder.build().unwrap();
     info!("initialized window");
 
+    sdl3::hint::set("SDL_RENDER_VSYNC", if config.vsync { "1" } else { "0" });
     let mut canvas = window.into_canvas();
     info!("initialized canvas");
 
-    let ttf_context = ttf::init().unwrap();
-    let font = ttf_context
-        .load_font("/usr/share/fonts/TTF/JetBrainsMono-Regular.ttf", 32.0)
-        .unwrap_or_else(|e| handle_font_error(e, ttf_context));
+    let ttf_context = ttf::init()
+        .inspect_err(|e| warn!("Couldn't initialize TTF: {}", e))
+        .ok();
+    let font_path = options.font.as_ref().or(config.font.as_ref());
+    let font_source = find_font(ttf_context.as_ref(), font_path.map(PathBuf::as_path));
     info!("initialized font");
 
-    let mut texture_creator = canvas.texture_creator();
+    let texture_creator = canvas.texture_creator();
+    let mut text = TextRenderer::new(&texture_creator, ttf_context.as_ref(), font_source);
+    let mut cells = CellLayer::new(&texture_creator);
+    let mut density = DensityPyramid::new();
 
     canvas.set_draw_color(Color::RGB(0, 255, 255));
     canvas.clear();
@@ -62,224 +175,377 @@ fn main() {
     let mut event_pump = sdl_context.event_pump().unwrap();
     info!("initialized event pump");
 
-    let grid = Grid::new();
     let mut gol = GOL::new(grid);
+    gol.rule = rule;
+    gol.paused = !options.running;
+    let mut color_mode = config.color_mode();
+    let mut grid_lines = config.grid.clone();
+    let mut minimap = Minimap::new(config.minimap);
+    let mut brush = Brush::new(config.brush_shape(), config.brush.size);
+    let mut tools = Tools::new();
+    gol.track_history(color_mode.needs_history());
     info!("initialized gol");
 
-    let mut viewstate: ViewState = Default::default();
+    let mut viewstate = ViewState {
+        zoom: options.zoom.unwrap_or(config.zoom),
+        ..Default::default()
+    };
+    let (camera_x, camera_y) = options.camera;
+    viewstate.center_on(camera_x, camera_y, canvas.output_size().unwrap());
+    let mut follow = Follow::new();
+    let mut transition: Option<Transition> = None;
+    if let Some(target) = options.follow {
+        follow.start(target, &gol, &viewstate, canvas.output_size().unwrap());
+    }
 
     let mut frame_time: Duration = Duration::from_millis(0);
     let mut mouse1_state = false;
     let mut mouse2_state = false;
-    let mut mouse3_state = false;
-
-    let mut drag_start: Vector2 = Vector2::new(0.0, 0.0);
-    let mut camera_start: Vector2 = Vector2::new(0.0, 0.0);
+    let mut motion = CameraMotion::new();
 
     let mut wasd_state = (false, false, false, false);
 
-    let mut speed = 14usize;
+    let mut pacing = Pacing::new(
+        options.gps.unwrap_or(Rate::from_gps(config.gps)),
+        options.step_exponent.unwrap_or(config.step_exponent),
+    );
+    // Only speeds changed while running are saved, not one-off `--gps` or `--step` overrides.
+    let startup_pacing = pacing;
+    let mut meter = RateMeter::new();
 
-    let (next_grid_request_tx, next_grid_request_rx) = mpsc::channel::<Grid>();
-    let (next_grid_result_tx, next_grid_result_rx) = mpsc::channel::<UpdateResult>();
+    let mut browser = PatternBrowser::new();
+    let mut goto = GotoDialog::new();
+    let mut bookmark_dialog = BookmarkDialog::new();
+    let mut random_fill = RandomFillDialog::new();
+    let mut stamp: Option<Grid> = None;
 
-    thread::spawn(move || {
-        while let Ok(current_grid_snapshot) = next_grid_request_rx.recv() {
-            let start = Instant::now();
-            let next = GOL::update_from(&current_grid_snapshot);
-            let compute_time = Instant::now() - start;
-            let result = UpdateResult { next_grid: next, compute_time };
-            if next_grid_result_tx.send(result).is_err() {
-                break;
-            }
-        }
-    });
-    
-    let mut update_in_progress = false;
+    let mut simulation = Simulation::spawn(&gol, pacing);
+    let frame_budget =
+        (config.max_fps > 0).then(|| Duration::from_secs_f32(1.0 / config.max_fps as f32));
 
     'running: loop {
         let start_time = Instant::now();
 
         for event in event_pump.poll_iter() {
-            match event {
-                Event::Quit { .. }
-                | Event::KeyDown {
-                    keycode: Some(Keycode::Escape),
-                    ..
-                } => break 'running,
-
-                Event::MouseButtonDown { mouse_btn, .. } => {
-                    if mouse_btn == MouseButton::Left {
-                        mouse1_state = true;
-                    } else if mouse_btn == MouseButton::Right {
-                        mouse2_state = true;
-                    } else if mouse_btn == MouseButton::Middle {
-                        mouse3_state = true;
-                        drag_start = viewstate.mouse_pos;
-                        camera_start = viewstate.camera_pos;
+            if browser.open && matches!(event, Event::KeyDown { .. } | Event::TextInput { .. }) {
+                match browser.handle_event(&event) {
+                    BrowserAction::None => continue,
+                    BrowserAction::Pick(pattern) => stamp = Some(pattern),
+                    BrowserAction::Close => {}
+                }
+                browser.open = false;
+                video_subsystem.text_input().stop(canvas.window());
+                continue;
+            }
+            if goto.open && matches!(event, Event::KeyDown { .. } | Event::TextInput { .. }) {
+                match goto.handle_event(&event) {
+                    GotoAction::None => continue,
+                    GotoAction::Go(x, y) => {
+                        follow.stop();
+                        motion.stop();
+                        transition = None;
+                        let output_size = canvas.output_size().unwrap();
+                        viewstate.center_on(x as f32 + 0.5, y as f32 + 0.5, output_size);
                     }
+                    GotoAction::Close => {}
                 }
-
-                Event::MouseButtonUp { mouse_btn, .. } => {
-                    if mouse_btn == MouseButton::Left {
-                        mouse1_state = false;
-                    } else if mouse_btn == MouseButton::Right {
-                        mouse2_state = false;
-                    } else if mouse_btn == MouseButton::Middle {
-                        mouse3_state = false;
+                goto.open = false;
+                video_subsystem.text_input().stop(canvas.window());
+                continue;
+            }
+            if bookmark_dialog.open
+                && matches!(event, Event::KeyDown { .. } | Event::TextInput { .. })
+            {
+                let output_size = canvas.output_size().unwrap();
+                match bookmark_dialog.handle_event(&event, &session.bookmarks) {
+                    BookmarkAction::None => continue,
+                    BookmarkAction::Delete(name) => {
+                        session.bookmarks.remove(&name);
+                        continue;
+                    }
+                    BookmarkAction::Jump(view) => {
+                        follow.stop();
+                        motion.stop();
+                        transition = Some(Transition::new(View::of(&viewstate, output_size), view));
                     }
+                    BookmarkAction::Save(name) => {
+                        session.bookmarks.insert(name, View::of(&viewstate, output_size));
+                    }
+                    BookmarkAction::Close => {}
                 }
+                bookmark_dialog.open = false;
+                video_subsystem.text_input().stop(canvas.window());
+                continue;
+            }
 
-                Event::KeyDown {
-                    keycode: Some(Keycode::R),
-                    ..
-                } => gol.grid.clear_all(),
-                Event::KeyDown {
-                    keycode: Some(Keycode::Space),
-                    ..
-                } => {
-                    gol.pause();
-                }
-                Event::KeyDown {
-                    keycode: Some(Keycode::Tab),
-                    ..
-                } => {
-                    gol.paused = false;
-                    gol.grid = GOL::update_from(&gol.grid.clone());
-                    gol.paused = true;
+            if random_fill.open
+                && matches!(event, Event::KeyDown { .. } | Event::TextInput { .. })
+            {
+                let selection_size = tools.selection.map(|(min, max)| {
+                    ((max.x - min.x + 1) as usize, (max.y - min.y + 1) as usize)
+                });
+                match random_fill.handle_event(&event, selection_size) {
+                    RandomFillAction::None => continue,
+                    RandomFillAction::Fill(soup) => {
+                        let origin = match tools.selection {
+                            Some((min, _)) => min,
+                            None => {
+                                let center = viewstate.center(canvas.output_size().unwrap());
+                                Vec2Isize::new(
+                                    center.x.floor() as isize - soup.width as isize / 2,
+                                    center.y.floor() as isize - soup.height as isize / 2,
+                                )
+                            }
+                        };
+                        soup.fill(gol.grid_mut(), origin);
+                        info!("filled {}, {} with soup {}", origin.x, origin.y, soup);
+                    }
+                    RandomFillAction::Close => {}
                 }
+                random_fill.open = false;
+                video_subsystem.text_input().stop(canvas.window());
+                continue;
+            }
 
+            match event {
                 Event::KeyDown {
-                    keycode: Some(Keycode::W),
-                    ..
-                } => {
-                    wasd_state.0 = true;
-                }
-                Event::KeyUp {
-                    keycode: Some(Keycode::W),
-                    ..
-                } => {
-                    wasd_state.0 = false;
-                }
-                Event::KeyDown {
-                    keycode: Some(Keycode::S),
+                    keycode: Some(keycode),
                     ..
-                } => {
-                    wasd_state.1 = true;
-                }
-                Event::KeyUp {
-                    keycode: Some(Keycode::S),
-                    ..
-                } => {
-                    wasd_state.1 = false;
+                } if stamp.is_some() && keymap.get(&keycode) == Some(&Action::Quit) => {
+                    stamp = None
                 }
                 Event::KeyDown {
-                    keycode: Some(Keycode::A),
-                    ..
-                } => {
-                    wasd_state.2 = true;
-                }
-                Event::KeyUp {
-                    keycode: Some(Keycode::A),
+                    keycode: Some(keycode),
                     ..
-                } => {
-                    wasd_state.2 = false;
+                } if tools.dragging() && keymap.get(&keycode) == Some(&Action::Quit) => {
+                    tools.cancel()
                 }
                 Event::KeyDown {
-                    keycode: Some(Keycode::D),
-                    ..
-                } => {
-                    wasd_state.3 = true;
-                }
-                Event::KeyUp {
-                    keycode: Some(Keycode::D),
+                    keycode: Some(keycode),
                     ..
-                } => {
-                    wasd_state.3 = false;
+                } if tools.selection.is_some() && keymap.get(&keycode) == Some(&Action::Quit) => {
+                    tools.selection = None
                 }
 
-                Event::KeyDown {
-                    keycode: Some(Keycode::Down),
-                    ..
-                } => {
-                    speed += 1;
-                }
-                Event::KeyDown {
-                    keycode: Some(Keycode::Up),
-                    ..
-                } => {
-                    let newspeed = speed as isize - 1;
+                Event::Quit { .. } => break 'running,
 
-                    if newspeed < 0 {
-                        continue;
+                Event::MouseButtonDown { mouse_btn, .. } if stamp.is_some() => {
+                    if mouse_btn == MouseButton::Left {
+                        let pattern = stamp.as_ref().unwrap();
+                        let origin = stamp_origin(pattern, &viewstate);
+                        gol.grid_mut().paste(pattern, origin.x, origin.y);
+                    } else if mouse_btn == MouseButton::Right {
+                        stamp = None;
                     }
-
-                    speed = newspeed as usize;
                 }
 
-                Event::MouseMotion { x, y, .. } => {
-                    viewstate.mouse_pos = Vector2::new(x, y);
+                Event::MouseButtonDown { mouse_btn, .. } => {
+                    let cell = viewstate.cursor_cell();
+                    if mouse_btn == MouseButton::Left {
+                        let output_size = canvas.output_size().unwrap();
+                        if minimap.press(&gol.grid, &mut density, &mut viewstate, output_size) {
+                            follow.stop();
+                            motion.stop();
+                            transition = None;
+                        } else if tools.tool.is_shape() {
+                            tools.start_drag(cell, true);
+                        } else if tools.tool == Tool::Fill {
+                            tools.fill(&mut gol, cell, true);
+                        } else {
+                            mouse1_state = true;
+                        }
+                    } else if mouse_btn == MouseButton::Right {
+                        if tools.tool == Tool::Select {
+                            tools.selection = None;
+                        } else if tools.tool.is_shape() {
+                            tools.start_drag(cell, false);
+                        } else if tools.tool == Tool::Fill {
+                            tools.fill(&mut gol, cell, false);
+                        } else {
+                            mouse2_state = true;
+                        }
+                    } else if mouse_btn == MouseButton::Middle {
+                        follow.stop();
+                        transition = None;
+                        motion.start_drag(&viewstate);
+                    }
                 }
-                Event::MouseWheel { y, .. } => {
-                    let old_scale = viewstate.zoom;
 
-                    let step: f32 = 1.1;
-                    let factor = step.powf(y);
-                    if !factor.is_finite() {
-                        continue;
+                Event::MouseButtonUp { mouse_btn, .. } => {
+                    if mouse_btn == MouseButton::Left {
+                        mouse1_state = false;
+                        brush.end_stroke();
+                        tools.end_drag(&mut gol, viewstate.cursor_cell());
+                        minimap.release();
+                    } else if mouse_btn == MouseButton::Right {
+                        mouse2_state = false;
+                        brush.end_stroke();
+                        tools.end_drag(&mut gol, viewstate.cursor_cell());
+                    } else if mouse_btn == MouseButton::Middle {
+                        motion.end_drag();
                     }
+                }
 
-                    let mut new_scale = old_scale * factor;
-
-                    if new_scale < 0.0001 {
-                        new_scale = 0.0001;
+                Event::KeyDown {
+                    keycode: Some(keycode),
+                    keymod,
+                    ..
+                } => match keymap.get(&keycode) {
+                    Some(Action::Quit) => break 'running,
+                    Some(Action::Clear) => gol.clear(),
+                    Some(Action::OpenBrowser) => {
+                        browser.open = true;
+                        browser.query.clear();
+                        browser.selected = 0;
+                        video_subsystem.text_input().start(canvas.window());
                     }
-                    if new_scale > 10000.0 {
-                        new_scale = 10000.0;
+                    Some(Action::Pause) => gol.pause(),
+                    Some(Action::Step) => {
+                        gol.paused = true;
+                        simulation.sync(&gol, pacing);
+                        simulation.step(pacing.step_size());
                     }
-
-                    if (new_scale / old_scale - 1.0).abs() < 1e-6 {
-                        continue;
+                    Some(Action::PanUp) => {
+                        wasd_state.0 = true;
+                        follow.stop();
+                        transition = None;
                     }
+                    Some(Action::PanDown) => {
+                        wasd_state.1 = true;
+                        follow.stop();
+                        transition = None;
+                    }
+                    Some(Action::PanLeft) => {
+                        wasd_state.2 = true;
+                        follow.stop();
+                        transition = None;
+                    }
+                    Some(Action::PanRight) => {
+                        wasd_state.3 = true;
+                        follow.stop();
+                        transition = None;
+                    }
+                    Some(Action::SlowDown) => pacing.slower(),
+                    Some(Action::SpeedUp) => pacing.faster(),
+                    Some(Action::BiggerSteps) => pacing.bigger_steps(),
+                    Some(Action::SmallerSteps) => pacing.smaller_steps(),
+                    Some(Action::CycleColorMode) => {
+                        color_mode = color_mode.next();
+                        gol.track_history(color_mode.needs_history());
+                    }
+                    Some(Action::BrushBigger) => brush.grow(),
+                    Some(Action::BrushSmaller) => brush.shrink(),
+                    Some(Action::CycleBrushShape) => brush.shape = brush.shape.next(),
+                    Some(Action::ToggleInvertBrush) => brush.invert = !brush.invert,
+                    Some(Action::BrushTool) => tools.select(Tool::Brush),
+                    Some(Action::LineTool) => tools.select(Tool::Line),
+                    Some(Action::RectTool) => tools.select(Tool::Rect { filled: false }),
+                    Some(Action::EllipseTool) => tools.select(Tool::Ellipse { filled: false }),
+                    Some(Action::FillTool) => tools.select(Tool::Fill),
+                    Some(Action::SelectTool) => tools.select(Tool::Select),
+                    Some(Action::RandomFill) => {
+                        random_fill.open = true;
+                        random_fill.input.clear();
+                        random_fill.error = None;
+                        video_subsystem.text_input().start(canvas.window());
+                    }
+                    Some(Action::ToggleGrid) => grid_lines.show = !grid_lines.show,
+                    Some(Action::ToggleMinimap) => minimap.show = !minimap.show,
+                    Some(Action::ZoomIn) => {
+                        transition = None;
+                        let (width, height) = canvas.output_size().unwrap();
+                        let center = Vector2::new(width as f32 / 2.0, height as f32 / 2.0);
+                        motion.zoom_by(&viewstate, KEY_ZOOM_STEP, center);
+                    }
+                    Some(Action::ZoomOut) => {
+                        transition = None;
+                        let (width, height) = canvas.output_size().unwrap();
+                        let center = Vector2::new(width as f32 / 2.0, height as f32 / 2.0);
+                        motion.zoom_by(&viewstate, 1.0 / KEY_ZOOM_STEP, center);
+                    }
+                    Some(Action::Follow) => match follow.target() {
+                        Some(_) => follow.stop(),
+                        None => {
+                            let output_size = canvas.output_size().unwrap();
+                            follow.start(FollowTarget::Object, &gol, &viewstate, output_size);
+                        }
+                    },
+                    Some(Action::Fit) => {
+                        follow.stop();
+                        motion.stop();
+                        transition = None;
+                        if let Some(bounds) = gol.grid.bounds() {
+                            viewstate.fit(bounds, canvas.output_size().unwrap());
+                        }
+                    }
+                    Some(Action::ResetOrigin) => {
+                        follow.stop();
+                        motion.stop();
+                        transition = None;
+                        viewstate.center_on(0.0, 0.0, canvas.output_size().unwrap());
+                    }
+                    Some(Action::GoTo) => {
+                        goto.open = true;
+                        goto.input.clear();
+                        goto.error = None;
+                        video_subsystem.text_input().start(canvas.window());
+                    }
+                    Some(Action::Bookmarks) => {
+                        bookmark_dialog.open = true;
+                        bookmark_dialog.input.clear();
+                        bookmark_dialog.selected = 0;
+                        video_subsystem.text_input().start(canvas.window());
+                    }
+                    None => {
+                        // Number keys recall the numbered bookmarks and save them with Ctrl held.
+                        if let Some(name) = bookmarks::numbered(keycode) {
+                            let output_size = canvas.output_size().unwrap();
+                            let current = View::of(&viewstate, output_size);
+                            if keymod.intersects(Mod::LCTRLMOD | Mod::RCTRLMOD) {
+                                session.bookmarks.insert(name.to_string(), current);
+                            } else if let Some(view) = session.bookmarks.get(name) {
+                                follow.stop();
+                                motion.stop();
+                                transition = Some(Transition::new(current, *view));
+                            }
+                        }
+                    }
+                },
+                Event::KeyUp {
+                    keycode: Some(keycode),
+                    ..
+                } => match keymap.get(&keycode) {
+                    Some(Action::PanUp) => wasd_state.0 = false,
+                    Some(Action::PanDown) => wasd_state.1 = false,
+                    Some(Action::PanLeft) => wasd_state.2 = false,
+                    Some(Action::PanRight) => wasd_state.3 = false,
+                    _ => {}
+                },
 
-                    let k = new_scale / old_scale;
-                    viewstate.camera_pos.x =
-                        k * viewstate.camera_pos.x + (1.0 - k) * viewstate.mouse_pos.x;
-                    viewstate.camera_pos.y =
-                        k * viewstate.camera_pos.y + (1.0 - k) * viewstate.mouse_pos.y;
-
-                    viewstate.zoom = new_scale;
+                Event::MouseMotion { x, y, .. } => {
+                    viewstate.mouse_pos = Vector2::new(x, y);
+                    motion.drag(&mut viewstate);
+                    let output_size = canvas.output_size().unwrap();
+                    minimap.drag(&gol.grid, &mut density, &mut viewstate, output_size);
+                }
+                Event::MouseWheel { y, .. } => {
+                    transition = None;
+                    let factor = WHEEL_ZOOM_STEP.powf(y);
+                    if factor.is_finite() {
+                        motion.zoom_by(&viewstate, factor, viewstate.mouse_pos);
+                    }
                 }
                 _ => {}
             }
         }
         
         if mouse1_state {
-            let rel_x = viewstate.mouse_pos.x - viewstate.camera_pos.x;
-            let rel_y = viewstate.mouse_pos.y - viewstate.camera_pos.y;
-            let col = (rel_x / viewstate.zoom).floor() as isize;
-            let row = (rel_y / viewstate.zoom).floor() as isize;
-            gol.grid.set_cell(row, col, true);
+            let stroke = if brush.invert { Stroke::Invert } else { Stroke::Draw };
+            brush.stroke_to(&mut gol, viewstate.cursor_cell(), stroke);
         } else if mouse2_state {
-            let rel_x = viewstate.mouse_pos.x - viewstate.camera_pos.x;
-            let rel_y = viewstate.mouse_pos.y - viewstate.camera_pos.y;
-            let col = (rel_x / viewstate.zoom).floor() as isize;
-            let row = (rel_y / viewstate.zoom).floor() as isize;
-            gol.grid.set_cell(row, col, false);
+            brush.stroke_to(&mut gol, viewstate.cursor_cell(), Stroke::Erase);
         }
-        if mouse3_state {
-            let mouse_delta = Vector2::new(
-                viewstate.mouse_pos.x - drag_start.x,
-                drag_start.y - viewstate.mouse_pos.y,
-            );
-            viewstate.camera_pos = Vector2::new(
-                camera_start.x - -mouse_delta.x,
-                camera_start.y - mouse_delta.y,
-            );
-        }
-        let wasd_speed =
-            1.0 * frame_time.as_micros() as f32 / (1000.0 - if gol.paused { 500.0 } else { 0.0 });
-
+        let wasd_speed = config.pan_speed * frame_time.as_secs_f32();
         if wasd_state.0 {
             viewstate.camera_pos.y += wasd_speed;
         }
@@ -293,39 +559,97 @@ fn main() {
             viewstate.camera_pos.x -= wasd_speed;
         }
 
-        if !gol.paused && !update_in_progress {
-            let current_grid_snapshot = gol.grid.clone();
-            if next_grid_request_tx.send(current_grid_snapshot).is_ok() {
-                update_in_progress = true;
-            }
+        simulation.sync(&gol, pacing);
+        let generations = simulation.poll(&mut gol);
+        if generations > 0 {
+            meter.record(generations);
         }
 
-        match next_grid_result_rx.try_recv() {
-            Ok(update) => {
-                thread::sleep(Duration::from_millis(speed as u64 / update.compute_time.as_millis().max(1) as u64));
-                gol.grid = update.next_grid;
-                update_in_progress = false;
-            }
-            Err(TryRecvError::Empty) => {}
-            Err(TryRecvError::Disconnected) => {
-                update_in_progress = false;
-            }
+        motion.update(&mut viewstate, frame_time);
+        let output_size = canvas.output_size().unwrap();
+        if let Some(flight) = transition
+            && !flight.apply(&mut viewstate, output_size)
+        {
+            transition = None;
         }
+        let smoothing = config.follow_smoothing;
+        follow.update(&gol, &mut viewstate, output_size, frame_time, smoothing);
 
         {
+            let (tool, selection) = (tools.tool, tools.selection);
+            let preview = tools.preview(&gol.grid, viewstate.cursor_cell(), !gol.paused);
             let mut render_ctx = RenderCtx {
-                gol: gol.clone(),
+                gol: &gol,
                 frame_time,
                 viewstate,
-                speed,
+                pacing,
+                achieved_gps: meter.rate(),
                 canvas: &mut canvas,
-                texture_creator: &mut texture_creator,
-                font: &font,
+                text: &mut text,
+                cells: &mut cells,
+                density: &mut density,
+                browser: &browser,
+                goto: &goto,
+                random_fill: &random_fill,
+                bookmark_dialog: &bookmark_dialog,
+                bookmarks: &session.bookmarks,
+                theme: &theme,
+                color_mode,
+                grid_lines: &grid_lines,
+                minimap: &mut minimap,
+                brush: &brush,
+                tool,
+                selection,
+                preview,
+                follow: follow.target(),
+                stamp: stamp
+                    .as_ref()
+                    .map(|pattern| (pattern, stamp_origin(pattern, &viewstate))),
             };
             draw_frame(&mut render_ctx);
         }
 
+        if let Some(budget) = frame_budget
+            && let Some(spare) = budget.checked_sub(start_time.elapsed())
+        {
+            thread::sleep(spare);
+        }
+
         let last_time = Instant::now();
         frame_time = last_time - start_time;
     }
+
+    if save_config && let Some(path) = &config_path {
+        let updated = Config {
+            gps: if pacing.rate != startup_pacing.rate {
+                pacing.rate.gps()
+            } else {
+                config.gps
+            },
+            step_exponent: if pacing.step_exponent != startup_pacing.step_exponent {
+                pacing.step_exponent
+            } else {
+                config.step_exponent
+            },
+            color_mode: color_mode.name().to_string(),
+            grid: grid_lines.clone(),
+            brush: BrushSettings {
+                size: brush.size,
+                shape: brush.shape.name().to_string(),
+            },
+            minimap: minimap.show,
+            ..config.clone()
+        };
+        if (updated != config || !path.exists())
+            && let Err(e) = updated.save(path)
+        {
+            warn!("Couldn't save config to {}: {}", path.display(), e);
+        }
+    }
+    if save_session
+        && let Some(path) = &session_path
+        && let Err(e) = session.save(path)
+    {
+        warn!("Couldn't save session to {}: {}", path.display(), e);
+    }
 }
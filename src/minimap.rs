@@ -0,0 +1,200 @@
+use crate::density::{DensityPyramid, MAX_LEVEL};
+use crate::theme::Theme;
+use rust_sdl_gol::camera::{Vector2, ViewState};
+use rust_sdl_gol::gol::{Grid, Vec2Isize};
+use sdl3::render::{BlendMode, Canvas, FRect};
+use sdl3::video::Window;
+
+const SIZE: f32 = 160.0;
+const MARGIN: f32 = 10.0;
+
+/// Cells of empty space kept around the pattern, and the area shown when there is no pattern.
+const PADDING: f32 = 16.0;
+
+/// Overview of the whole pattern in the bottom-left corner, with the viewport outlined.
+/// Clicking it centres the view on that spot and dragging keeps the view following the mouse.
+pub struct Minimap {
+    pub show: bool,
+    dragging: bool,
+    /// Pyramid level drawn last, whose blocks are about a minimap pixel across. The bounds are
+    /// read from it, which is as precise as the minimap can show.
+    level: u32,
+    /// Pattern bounds as `(min, max)` cell corners, cached for the grid version in `bounds_version`.
+    bounds: Option<(Vec2Isize, Vec2Isize)>,
+    bounds_version: Option<u64>,
+}
+
+/// Where the minimap sits on screen and which cells it covers.
+#[derive(Debug, Clone, Copy)]
+struct Layout {
+    area: FRect,
+    /// Cell coordinate at the area's top-left corner.
+    origin: Vector2,
+    /// Minimap pixels per cell.
+    scale: f32,
+}
+
+impl Layout {
+    fn to_screen(self, x: f32, y: f32) -> (f32, f32) {
+        (
+            self.area.x + (x - self.origin.x) * self.scale,
+            self.area.y + (y - self.origin.y) * self.scale,
+        )
+    }
+
+    fn contains(self, x: f32, y: f32) -> bool {
+        (self.area.x..self.area.x + self.area.w).contains(&x)
+            && (self.area.y..self.area.y + self.area.h).contains(&y)
+    }
+}
+
+impl Minimap {
+    pub fn new(show: bool) -> Self {
+        Minimap {
+            show,
+            dragging: false,
+            level: 0,
+            bounds: None,
+            bounds_version: None,
+        }
+    }
+
+    fn layout(
+        &mut self,
+        grid: &Grid,
+        pyramid: &mut DensityPyramid,
+        output_size: (u32, u32),
+    ) -> Layout {
+        if self.bounds_version != Some(grid.version()) {
+            self.bounds = pyramid.bounds(grid, self.level);
+            self.bounds_version = Some(grid.version());
+        }
+        let (min, max) = match self.bounds {
+            Some((min, max)) => (
+                Vector2::new(min.x as f32 - PADDING, min.y as f32 - PADDING),
+                Vector2::new(max.x as f32 + 1.0 + PADDING, max.y as f32 + 1.0 + PADDING),
+            ),
+            None => (
+                Vector2::new(-PADDING, -PADDING),
+                Vector2::new(PADDING, PADDING),
+            ),
+        };
+
+        // Fit the longer side of the bounds and centre the shorter one.
+        let (width, height) = (max.x - min.x, max.y - min.y);
+        let scale = SIZE / width.max(height);
+        let origin = Vector2::new(
+            min.x - (SIZE / scale - width) / 2.0,
+            min.y - (SIZE / scale - height) / 2.0,
+        );
+        let area = FRect::new(MARGIN, output_size.1 as f32 - SIZE - MARGIN, SIZE, SIZE);
+        Layout {
+            area,
+            origin,
+            scale,
+        }
+    }
+
+    /// Handles a left click at the mouse position. Returns false if the click missed the minimap
+    /// and should go to the grid instead.
+    pub fn press(
+        &mut self,
+        grid: &Grid,
+        pyramid: &mut DensityPyramid,
+        viewstate: &mut ViewState,
+        output_size: (u32, u32),
+    ) -> bool {
+        let mouse = viewstate.mouse_pos;
+        if !self.show
+            || !self
+                .layout(grid, pyramid, output_size)
+                .contains(mouse.x, mouse.y)
+        {
+            return false;
+        }
+        self.dragging = true;
+        self.drag(grid, pyramid, viewstate, output_size);
+        true
+    }
+
+    /// Keeps the view centred under the mouse while a drag that started on the minimap lasts.
+    pub fn drag(
+        &mut self,
+        grid: &Grid,
+        pyramid: &mut DensityPyramid,
+        viewstate: &mut ViewState,
+        output_size: (u32, u32),
+    ) {
+        if !self.dragging {
+            return;
+        }
+        let layout = self.layout(grid, pyramid, output_size);
+        let mouse = viewstate.mouse_pos;
+        let x = layout.origin.x + (mouse.x - layout.area.x).clamp(0.0, SIZE) / layout.scale;
+        let y = layout.origin.y + (mouse.y - layout.area.y).clamp(0.0, SIZE) / layout.scale;
+        viewstate.center_on(x, y, output_size);
+    }
+
+    pub fn release(&mut self) {
+        self.dragging = false;
+    }
+
+    pub fn draw(
+        &mut self,
+        canvas: &mut Canvas<Window>,
+        grid: &Grid,
+        pyramid: &mut DensityPyramid,
+        viewstate: ViewState,
+        theme: &Theme,
+    ) {
+        if !self.show {
+            return;
+        }
+        let (width, height) = canvas.output_size().unwrap();
+        let layout = self.layout(grid, pyramid, (width, height));
+
+        canvas.set_blend_mode(BlendMode::Blend);
+        canvas.set_draw_color(theme.panel);
+        canvas.fill_rect(layout.area).unwrap();
+
+        // Blocks from the deepest level that still fits one per minimap pixel.
+        let level = ((1.0 / layout.scale).log2().floor().max(0.0) as u32).min(MAX_LEVEL);
+        self.level = level;
+        let block_size = (1isize << level) as f32;
+        let dot = (block_size * layout.scale).max(1.0);
+        let to_rect = |block: Vec2Isize| {
+            let (x, y) = layout.to_screen(block.x as f32 * block_size, block.y as f32 * block_size);
+            FRect::new(x, y, dot, dot)
+        };
+        let blocks: Vec<FRect> = if level == 0 {
+            grid.iter().map(to_rect).collect()
+        } else {
+            pyramid
+                .blocks(grid, level)
+                .map(|(block, _)| to_rect(block))
+                .collect()
+        };
+        canvas.set_draw_color(theme.cell);
+        canvas.fill_rects(&blocks).unwrap();
+
+        // The viewport, clipped to the minimap so it stays visible when far away.
+        let top_left = viewstate.screen_to_world(Vector2::new(0.0, 0.0));
+        let bottom_right = viewstate.screen_to_world(Vector2::new(width as f32, height as f32));
+        let (left, top) = layout.to_screen(top_left.x, top_left.y);
+        let (right, bottom) = layout.to_screen(bottom_right.x, bottom_right.y);
+        let area = layout.area;
+        let clip_x = |x: f32| x.clamp(area.x, area.x + area.w);
+        let clip_y = |y: f32| y.clamp(area.y, area.y + area.h);
+        let (left, right) = (clip_x(left), clip_x(right));
+        let (top, bottom) = (clip_y(top), clip_y(bottom));
+        canvas.set_draw_color(theme.viewport);
+        canvas
+            .draw_rect(FRect::new(
+                left,
+                top,
+                (right - left).max(1.0),
+                (bottom - top).max(1.0),
+            ))
+            .unwrap();
+    }
+}
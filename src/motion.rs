@@ -0,0 +1,140 @@
+use rust_sdl_gol::camera::{MAX_ZOOM, MIN_ZOOM, Vector2, ViewState};
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// Zoom factor per mouse wheel notch.
+pub const WHEEL_ZOOM_STEP: f32 = 1.1;
+
+/// Zoom factor per press of the zoom keys.
+pub const KEY_ZOOM_STEP: f32 = 1.5;
+
+/// Time constant of the eased zoom, in seconds.
+const ZOOM_EASE: f32 = 0.08;
+
+/// Time constant with which a released drag slows down, in seconds.
+const GLIDE_FRICTION: f32 = 0.3;
+
+/// Glides slower than this, in pixels per second, stop.
+const MIN_GLIDE_SPEED: f32 = 10.0;
+
+/// Mouse movement this recent sets the speed a released drag glides on with.
+const RELEASE_WINDOW: Duration = Duration::from_millis(80);
+
+struct Drag {
+    camera_start: Vector2,
+    mouse_start: Vector2,
+    /// Mouse positions within `RELEASE_WINDOW` of the latest one.
+    samples: VecDeque<(Instant, Vector2)>,
+}
+
+/// Camera movement that plays out over several frames: eased zooming, middle-button dragging and
+/// the glide after a drag is released.
+pub struct CameraMotion {
+    /// Zoom being eased towards, and the screen point that stays put while zooming.
+    zoom_goal: Option<(f32, Vector2)>,
+    drag: Option<Drag>,
+    /// Glide speed in pixels per second.
+    velocity: Vector2,
+}
+
+impl CameraMotion {
+    pub fn new() -> Self {
+        CameraMotion {
+            zoom_goal: None,
+            drag: None,
+            velocity: Vector2::default(),
+        }
+    }
+
+    /// Stops any zoom or glide in progress, for when something else takes over the camera.
+    pub fn stop(&mut self) {
+        self.zoom_goal = None;
+        self.velocity = Vector2::default();
+    }
+
+    /// Eases the zoom by `factor` on top of any zoom still in progress, keeping the screen point
+    /// `anchor` over the same cell.
+    pub fn zoom_by(&mut self, viewstate: &ViewState, factor: f32, anchor: Vector2) {
+        let from = self.zoom_goal.map_or(viewstate.zoom, |(goal, _)| goal);
+        let goal = (from * factor).clamp(MIN_ZOOM, MAX_ZOOM);
+        self.zoom_goal = Some((goal, anchor));
+    }
+
+    pub fn start_drag(&mut self, viewstate: &ViewState) {
+        self.velocity = Vector2::default();
+        self.drag = Some(Drag {
+            camera_start: viewstate.camera_pos,
+            mouse_start: viewstate.mouse_pos,
+            samples: VecDeque::from([(Instant::now(), viewstate.mouse_pos)]),
+        });
+    }
+
+    /// Keeps the grid under the mouse while dragging.
+    pub fn drag(&mut self, viewstate: &mut ViewState) {
+        let Some(drag) = &mut self.drag else {
+            return;
+        };
+        let mouse = viewstate.mouse_pos;
+        viewstate.camera_pos = Vector2::new(
+            drag.camera_start.x + mouse.x - drag.mouse_start.x,
+            drag.camera_start.y + mouse.y - drag.mouse_start.y,
+        );
+
+        let now = Instant::now();
+        drag.samples.push_back((now, mouse));
+        while drag
+            .samples
+            .front()
+            .is_some_and(|(time, _)| now - *time > RELEASE_WINDOW)
+        {
+            drag.samples.pop_front();
+        }
+    }
+
+    /// Ends the drag, letting the camera glide on at the speed the mouse was last moving.
+    pub fn end_drag(&mut self) {
+        let Some(drag) = self.drag.take() else {
+            return;
+        };
+        let (Some(&(first_time, first)), Some(&(last_time, last))) =
+            (drag.samples.front(), drag.samples.back())
+        else {
+            return;
+        };
+        let span = (last_time - first_time).as_secs_f32();
+        // A mouse that stopped before the release leaves nothing to glide on.
+        if span <= 0.0 || last_time.elapsed() > RELEASE_WINDOW {
+            return;
+        }
+        self.velocity = Vector2::new((last.x - first.x) / span, (last.y - first.y) / span);
+    }
+
+    /// Advances the zoom and glide by `frame_time`.
+    pub fn update(&mut self, viewstate: &mut ViewState, frame_time: Duration) {
+        let dt = frame_time.as_secs_f32();
+
+        if self.drag.is_none() && (self.velocity.x != 0.0 || self.velocity.y != 0.0) {
+            viewstate.camera_pos.x += self.velocity.x * dt;
+            viewstate.camera_pos.y += self.velocity.y * dt;
+            let decay = (-dt / GLIDE_FRICTION).exp();
+            self.velocity = Vector2::new(self.velocity.x * decay, self.velocity.y * decay);
+            if self.velocity.x.hypot(self.velocity.y) < MIN_GLIDE_SPEED {
+                self.velocity = Vector2::default();
+            }
+        }
+
+        if let Some((goal, anchor)) = self.zoom_goal {
+            let old = viewstate.zoom;
+            let t = 1.0 - (-dt / ZOOM_EASE).exp();
+            let mut new = old * (goal / old).powf(t);
+            if (new / goal - 1.0).abs() < 1e-3 {
+                new = goal;
+                self.zoom_goal = None;
+            }
+            let k = new / old;
+            viewstate.camera_pos.x = k * viewstate.camera_pos.x + (1.0 - k) * anchor.x;
+            viewstate.camera_pos.y = k * viewstate.camera_pos.y + (1.0 - k) * anchor.y;
+            viewstate.zoom = new;
+        }
+    }
+}
@@ -0,0 +1,162 @@
+use std::collections::VecDeque;
+use std::fmt;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+/// Fastest capped rate. Speeding up past it runs uncapped.
+pub const MAX_GPS: f32 = 4096.0;
+pub const MIN_GPS: f32 = 0.25;
+
+/// Largest step size, as a power of two.
+pub const MAX_STEP_EXPONENT: u32 = 16;
+
+/// How far back the achieved rate is measured over.
+const METER_WINDOW: Duration = Duration::from_secs(1);
+
+/// Target simulation speed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Rate {
+    /// Generations per second.
+    Capped(f32),
+    /// As fast as the simulation can go.
+    Uncapped,
+}
+
+impl Rate {
+    /// Reads the config's `gps`, where 0 means uncapped.
+    pub fn from_gps(gps: f32) -> Rate {
+        if gps > 0.0 && gps.is_finite() {
+            Rate::Capped(gps.clamp(MIN_GPS, MAX_GPS))
+        } else {
+            Rate::Uncapped
+        }
+    }
+
+    /// The rate as stored in the config's `gps`.
+    pub fn gps(self) -> f32 {
+        match self {
+            Rate::Capped(gps) => gps,
+            Rate::Uncapped => 0.0,
+        }
+    }
+}
+
+impl fmt::Display for Rate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Rate::Capped(gps) => write!(f, "{}", gps),
+            Rate::Uncapped => f.write_str("max"),
+        }
+    }
+}
+
+/// Parses a rate in generations per second, or `max` for uncapped.
+impl FromStr for Rate {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.trim().eq_ignore_ascii_case("max") {
+            return Ok(Rate::Uncapped);
+        }
+        match s.trim().parse::<f32>() {
+            Ok(gps) if (MIN_GPS..=MAX_GPS).contains(&gps) => Ok(Rate::Capped(gps)),
+            _ => Err(format!(
+                "generations per second must be `max` or between {} and {}",
+                MIN_GPS, MAX_GPS
+            )),
+        }
+    }
+}
+
+/// How fast the simulation runs: a target rate, reached by advancing `2^step_exponent`
+/// generations per update.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Pacing {
+    pub rate: Rate,
+    pub step_exponent: u32,
+}
+
+impl Pacing {
+    pub fn new(rate: Rate, step_exponent: u32) -> Self {
+        Pacing {
+            rate,
+            step_exponent: step_exponent.min(MAX_STEP_EXPONENT),
+        }
+    }
+
+    /// Generations advanced per update.
+    pub fn step_size(&self) -> u64 {
+        1 << self.step_exponent
+    }
+
+    /// Time between the starts of updates that keeps to the target rate, or `None` when
+    /// uncapped.
+    pub fn update_interval(&self) -> Option<Duration> {
+        match self.rate {
+            Rate::Capped(gps) => Some(Duration::from_secs_f32(self.step_size() as f32 / gps)),
+            Rate::Uncapped => None,
+        }
+    }
+
+    /// Doubles the rate, going uncapped past `MAX_GPS`.
+    pub fn faster(&mut self) {
+        if let Rate::Capped(gps) = self.rate {
+            self.rate = if gps * 2.0 > MAX_GPS {
+                Rate::Uncapped
+            } else {
+                Rate::Capped(gps * 2.0)
+            };
+        }
+    }
+
+    /// Halves the rate, coming down from uncapped to `MAX_GPS`.
+    pub fn slower(&mut self) {
+        self.rate = match self.rate {
+            Rate::Capped(gps) => Rate::Capped((gps / 2.0).max(MIN_GPS)),
+            Rate::Uncapped => Rate::Capped(MAX_GPS),
+        };
+    }
+
+    pub fn bigger_steps(&mut self) {
+        self.step_exponent = (self.step_exponent + 1).min(MAX_STEP_EXPONENT);
+    }
+
+    pub fn smaller_steps(&mut self) {
+        self.step_exponent = self.step_exponent.saturating_sub(1);
+    }
+}
+
+/// Measures the generations per second actually achieved over the last second.
+pub struct RateMeter {
+    /// When each recent update finished and how many generations it advanced.
+    updates: VecDeque<(Instant, u64)>,
+}
+
+impl RateMeter {
+    pub fn new() -> Self {
+        RateMeter {
+            updates: VecDeque::new(),
+        }
+    }
+
+    pub fn record(&mut self, generations: u64) {
+        self.updates.push_back((Instant::now(), generations));
+    }
+
+    pub fn rate(&mut self) -> f32 {
+        let now = Instant::now();
+        while self
+            .updates
+            .front()
+            .is_some_and(|(time, _)| now - *time > METER_WINDOW)
+        {
+            self.updates.pop_front();
+        }
+        let generations: u64 = self
+            .updates
+            .iter()
+            .map(|(_, generations)| generations)
+            .sum();
+        generations as f32 / METER_WINDOW.as_secs_f32()
+    }
+}
@@ -0,0 +1,96 @@
+use crate::formats::parse_rle;
+use crate::gol::Grid;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Category {
+    StillLife,
+    Oscillator,
+    Spaceship,
+    Gun,
+    Puffer,
+    Methuselah,
+}
+
+impl Category {
+    pub fn name(self) -> &'static str {
+        match self {
+            Category::StillLife => "still life",
+            Category::Oscillator => "oscillator",
+            Category::Spaceship => "spaceship",
+            Category::Gun => "gun",
+            Category::Puffer => "puffer",
+            Category::Methuselah => "methuselah",
+        }
+    }
+}
+
+struct BundledPattern {
+    category: Category,
+    rle: &'static str,
+}
+
+macro_rules! bundled {
+    ($category:ident, $file:literal) => {
+        BundledPattern {
+            category: Category::$category,
+            rle: include_str!(concat!("../patterns/", $file, ".rle")),
+        }
+    };
+}
+
+const BUNDLED: &[BundledPattern] = &[
+    bundled!(StillLife, "block"),
+    bundled!(StillLife, "beehive"),
+    bundled!(StillLife, "loaf"),
+    bundled!(StillLife, "boat"),
+    bundled!(StillLife, "tub"),
+    bundled!(Oscillator, "blinker"),
+    bundled!(Oscillator, "toad"),
+    bundled!(Oscillator, "beacon"),
+    bundled!(Oscillator, "pulsar"),
+    bundled!(Oscillator, "pentadecathlon"),
+    bundled!(Spaceship, "glider"),
+    bundled!(Spaceship, "lwss"),
+    bundled!(Spaceship, "mwss"),
+    bundled!(Spaceship, "hwss"),
+    bundled!(Gun, "gosper_glider_gun"),
+    bundled!(Puffer, "switch_engine_5x5"),
+    bundled!(Puffer, "infinite_growth_line"),
+    bundled!(Methuselah, "r_pentomino"),
+    bundled!(Methuselah, "diehard"),
+    bundled!(Methuselah, "acorn"),
+    bundled!(Methuselah, "pi_heptomino"),
+    bundled!(Methuselah, "b_heptomino"),
+];
+
+#[derive(Debug, Clone)]
+pub struct Pattern {
+    pub name: String,
+    pub category: Category,
+    pub grid: Grid,
+}
+
+impl Pattern {
+    /// Case-insensitive match of `query` against the pattern's name and category.
+    pub fn matches(&self, query: &str) -> bool {
+        let query = query.trim().to_lowercase();
+        query.is_empty()
+            || self.name.to_lowercase().contains(&query)
+            || self.category.name().contains(&query)
+    }
+}
+
+/// Parses every pattern bundled into the binary.
+pub fn load_library() -> Vec<Pattern> {
+    BUNDLED
+        .iter()
+        .map(|bundled| {
+            let data = parse_rle(bundled.rle).expect("bundled pattern is valid RLE");
+            Pattern {
+                name: data.name.unwrap_or_default(),
+                category: bundled.category,
+                grid: data.grid,
+            }
+        })
+        .collect()
+}
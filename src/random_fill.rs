@@ -0,0 +1,133 @@
+use rust_sdl_gol::soup::{Soup, Symmetry};
+use sdl3::event::Event;
+use sdl3::keyboard::Keycode;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Widest and tallest soup the dialog will make.
+const MAX_SOUP_SIZE: usize = 4096;
+
+const DEFAULT_SOUP: Soup = Soup {
+    width: 16,
+    height: 16,
+    density: 0.5,
+    symmetry: Symmetry::C1,
+    seed: 0,
+};
+
+pub enum RandomFillAction {
+    None,
+    Close,
+    Fill(Soup),
+}
+
+/// Text prompt for a random soup, as any of `<w>x<h>`, `<density>%`, a symmetry and a seed in
+/// any order. Anything left out is taken from the previous soup, except the seed, which is new
+/// each time unless given.
+pub struct RandomFillDialog {
+    pub open: bool,
+    pub input: String,
+    /// Why the last submitted input was rejected.
+    pub error: Option<String>,
+    /// The soup most recently made, so it can be rerolled or reproduced.
+    pub last: Option<Soup>,
+}
+
+/// A seed that differs from run to run.
+fn fresh_seed() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_nanos() as u64)
+}
+
+/// Parses the dialog input on top of `base`. `size` is the selection's size, used when the
+/// input doesn't give one.
+fn parse_soup(input: &str, base: Soup, size: Option<(usize, usize)>) -> Result<Soup, String> {
+    let mut soup = base;
+    if let Some((width, height)) = size {
+        soup.width = width;
+        soup.height = height;
+    }
+    soup.seed = fresh_seed();
+
+    for token in input.split_whitespace() {
+        if let Some(percent) = token.strip_suffix('%') {
+            let density: f64 = percent
+                .parse()
+                .map_err(|_| format!("`{}` is not a percentage", token))?;
+            if !(0.0..=100.0).contains(&density) {
+                return Err("density must be between 0% and 100%".to_string());
+            }
+            soup.density = density / 100.0;
+        } else if let Some((width, height)) = token.split_once('x') {
+            let parse = |value: &str| {
+                value
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|&n| n > 0)
+                    .ok_or_else(|| format!("`{}` is not a size like `16x16`", token))
+            };
+            soup.width = parse(width)?;
+            soup.height = parse(height)?;
+        } else if let Ok(seed) = token.parse::<u64>() {
+            soup.seed = seed;
+        } else {
+            soup.symmetry = token.parse()?;
+        }
+    }
+
+    if soup.width > MAX_SOUP_SIZE || soup.height > MAX_SOUP_SIZE {
+        return Err(format!("soups are at most {} cells across", MAX_SOUP_SIZE));
+    }
+    if soup.symmetry.needs_square() && soup.width != soup.height {
+        return Err(format!("{} soups must be square", soup.symmetry));
+    }
+    Ok(soup)
+}
+
+impl RandomFillDialog {
+    pub fn new() -> RandomFillDialog {
+        RandomFillDialog {
+            open: false,
+            input: String::new(),
+            error: None,
+            last: None,
+        }
+    }
+
+    /// `selection` is the size of the selected rectangle, if there is one.
+    pub fn handle_event(
+        &mut self,
+        event: &Event,
+        selection: Option<(usize, usize)>,
+    ) -> RandomFillAction {
+        match event {
+            Event::TextInput { text, .. } => {
+                self.input.push_str(text);
+                self.error = None;
+            }
+            Event::KeyDown {
+                keycode: Some(keycode),
+                ..
+            } => match *keycode {
+                Keycode::Escape => return RandomFillAction::Close,
+                Keycode::Backspace => {
+                    self.input.pop();
+                    self.error = None;
+                }
+                Keycode::Return => {
+                    let base = self.last.unwrap_or(DEFAULT_SOUP);
+                    match parse_soup(&self.input, base, selection) {
+                        Ok(soup) => {
+                            self.last = Some(soup);
+                            return RandomFillAction::Fill(soup);
+                        }
+                        Err(e) => self.error = Some(e),
+                    }
+                }
+                _ => {}
+            },
+            _ => {}
+        }
+        RandomFillAction::None
+    }
+}
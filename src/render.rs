@@ -1,159 +1,570 @@
-use crate::gol::Grid;
-use crate::types::{RenderCtx, ViewState};
-use sdl3::pixels::Color;
-use sdl3::render::{Canvas, FPoint, FRect, TextureCreator};
-use sdl3::ttf::Font;
-use sdl3::video::{Window, WindowContext};
+use crate::bookmarks::{BookmarkDialog, View};
+use crate::browser::PatternBrowser;
+use crate::coloring::{CellPaint, ColorMode};
+use crate::config::GridLines;
+use crate::goto::GotoDialog;
+use crate::random_fill::RandomFillDialog;
+use crate::text::{Align, TextRenderer, TextStyle};
+use crate::theme::Theme;
+use crate::tools::Tool;
+use crate::types::RenderCtx;
+use rust_sdl_gol::camera::{Vector2, ViewState};
+use rust_sdl_gol::gol::{Grid, Vec2Isize};
+use sdl3::render::{BlendMode, Canvas, FRect};
+use sdl3::video::Window;
+use std::collections::BTreeMap;
 
+const HUD_TEXT_SIZE: f32 = 18.0;
 
 pub fn draw_frame(render_ctx: &mut RenderCtx) {
-    render_ctx.canvas.set_draw_color(Color::RGB(0, 0, 0));
+    let theme = render_ctx.theme;
+    render_ctx.canvas.set_draw_color(theme.background);
     render_ctx.canvas.clear();
 
-    draw_cells(&mut render_ctx.gol.grid, render_ctx.canvas, render_ctx.viewstate);
-
-    draw_selection(render_ctx.canvas, &render_ctx.gol.grid, render_ctx.viewstate);
-
-    draw_text(
-        render_ctx.font,
+    let paint = CellPaint {
+        mode: render_ctx.color_mode,
+        theme,
+        history: render_ctx.gol.history.as_ref(),
+    };
+    render_ctx.cells.draw(
         render_ctx.canvas,
-        render_ctx.frame_time.as_millis().to_string().as_str(),
-        24.0,
-        Color::RGB(255, 255, 255),
-        10.0,
-        16.0,
-        render_ctx.texture_creator,
+        &render_ctx.gol.grid,
+        render_ctx.density,
+        render_ctx.viewstate,
+        &paint,
     );
-    if render_ctx.gol.paused {
-        draw_text(
-            render_ctx.font,
+    draw_grid_lines(render_ctx.canvas, render_ctx.viewstate, render_ctx.grid_lines, theme);
+
+    if let Some((stamp, origin)) = render_ctx.stamp {
+        draw_stamp_preview(render_ctx.canvas, stamp, origin, render_ctx.viewstate, theme);
+    } else {
+        if let Some(selection) = render_ctx.selection {
+            draw_marquee(render_ctx.canvas, selection, render_ctx.viewstate, theme);
+        }
+        if let Some((cells, draw)) = render_ctx.preview {
+            draw_tool_preview(render_ctx.canvas, cells, draw, render_ctx.viewstate, theme);
+        }
+        let footprint = match render_ctx.tool {
+            Tool::Brush => render_ctx.brush.footprint(),
+            _ => vec![Vec2Isize::new(0, 0)],
+        };
+        draw_selection(
             render_ctx.canvas,
-            "PAUSED",
-            24.0,
-            Color::RGB(255, 0, 0),
-            10.0,
-            32.0,
-            render_ctx.texture_creator,
-        )
+            &render_ctx.gol.grid,
+            &footprint,
+            render_ctx.viewstate,
+            theme,
+        );
     }
 
-    draw_text(
-        render_ctx.font,
-        render_ctx.canvas,
-        format!("{:.3}x zoom", render_ctx.viewstate.zoom).as_str(),
-        24.0,
-        Color::RGB(255, 255, 255),
-        10.0,
-        48.0,
-        render_ctx.texture_creator,
-    );
+    let mut hud = vec![
+        (render_ctx.frame_time.as_millis().to_string(), theme.text),
+        (format!("{:.3}x zoom", render_ctx.viewstate.zoom), theme.text),
+        (gps_line(render_ctx), theme.text),
+    ];
+    let cursor = render_ctx.viewstate.cursor_cell();
+    hud.push((format!("x {} y {}", cursor.x, cursor.y), theme.text));
+    let brush = render_ctx.brush;
+    if render_ctx.tool != Tool::Brush {
+        hud.push((format!("{} tool", render_ctx.tool.name()), theme.text));
+    } else if brush.size > 1 || brush.invert {
+        let invert = if brush.invert { " inverting" } else { "" };
+        let line = format!("{} {} brush{}", brush.size, brush.shape.name(), invert);
+        hud.push((line, theme.text));
+    }
+    if let Some(soup) = render_ctx.random_fill.last {
+        hud.push((format!("soup {}", soup), theme.text));
+    }
+    if let Some(target) = render_ctx.follow {
+        hud.push((format!("following {}", target), theme.text));
+    }
+    if render_ctx.color_mode != ColorMode::Plain {
+        // Zoomed out, pixels are shaded by how many cells they cover and carry no colours.
+        let line = if render_ctx.viewstate.zoom < 1.0 {
+            format!("{} colours from 1x zoom", render_ctx.color_mode.name())
+        } else {
+            format!("{} colours", render_ctx.color_mode.name())
+        };
+        hud.push((line, theme.text));
+    }
+    let mut y = 10.0;
+    for (line, color) in hud {
+        let style = TextStyle::new(HUD_TEXT_SIZE, color);
+        render_ctx.text.draw(render_ctx.canvas, &line, 10.0, y, style);
+        y += render_ctx.text.measure(&line, HUD_TEXT_SIZE).1;
+    }
+    if render_ctx.gol.paused {
+        let (width, _) = render_ctx.canvas.output_size().unwrap();
+        let style = TextStyle::new(HUD_TEXT_SIZE, theme.paused_text).aligned(Align::Center);
+        render_ctx.text.draw(render_ctx.canvas, "PAUSED", width as f32 / 2.0, 10.0, style);
+    }
 
-    draw_text(
-        render_ctx.font,
+    render_ctx.minimap.draw(
         render_ctx.canvas,
-        format!("{}/ speed", render_ctx.speed).as_str(),
-        24.0,
-        Color::RGB(255, 255, 255),
-        10.0,
-        64.0,
-        render_ctx.texture_creator,
+        &render_ctx.gol.grid,
+        render_ctx.density,
+        render_ctx.viewstate,
+        theme,
     );
 
+    if render_ctx.browser.open {
+        draw_browser(render_ctx.text, render_ctx.canvas, render_ctx.browser, theme);
+    }
+    if render_ctx.goto.open {
+        draw_goto(render_ctx.text, render_ctx.canvas, render_ctx.goto, theme);
+    }
+    if render_ctx.random_fill.open {
+        draw_random_fill(render_ctx.text, render_ctx.canvas, render_ctx.random_fill, theme);
+    }
+    if render_ctx.bookmark_dialog.open {
+        draw_bookmarks(
+            render_ctx.text,
+            render_ctx.canvas,
+            render_ctx.bookmark_dialog,
+            render_ctx.bookmarks,
+            theme,
+        );
+    }
+
     render_ctx.canvas.present();
 }
 
-fn round_down_to_multiple(n: f32, step: f32) -> f32 {
-    (n / step).floor() * step
+/// Multiples of `step` between `first` and `last` inclusive.
+fn multiples_between(first: isize, last: isize, step: isize) -> impl Iterator<Item = isize> {
+    let start = first.div_euclid(step) * step;
+    let start = if start < first { start + step } else { start };
+    (start..=last).step_by(step as usize)
 }
 
-fn draw_text(
-    font: &Font,
+/// Pixels across the major grid lines and the axes. Minor lines are one pixel.
+const MAJOR_LINE_WIDTH: f32 = 2.0;
+
+fn draw_grid_lines(
     canvas: &mut Canvas<Window>,
-    text: &str,
-    font_size: f32,
-    color: Color,
-    x: f32,
-    y: f32,
-    texture_creator: &mut TextureCreator<WindowContext>,
+    viewstate: ViewState,
+    grid_lines: &GridLines,
+    theme: &Theme,
 ) {
-    let text_texture = texture_creator
-        .create_texture_from_surface(
-            font.render(format!("{}", text).as_str())
-                .blended(color)
-                .unwrap(),
-        )
-        .unwrap();
+    if !grid_lines.show {
+        return;
+    }
+    let (width, height) = canvas.output_size().unwrap();
+    let (width, height) = (width as f32, height as f32);
+    let zoom = viewstate.zoom;
 
-    canvas
-        .copy(
-            &text_texture,
-            None,
+    // Cell boundaries inside the window, as column and row indices.
+    let top_left = viewstate.screen_to_world(Vector2::new(0.0, 0.0));
+    let bottom_right = viewstate.screen_to_world(Vector2::new(width, height));
+    let first_col = top_left.x.ceil() as isize;
+    let last_col = bottom_right.x.floor() as isize;
+    let first_row = top_left.y.ceil() as isize;
+    let last_row = bottom_right.y.floor() as isize;
+    // Lines `thickness` pixels wide, centred on the boundary.
+    let vertical = |col: isize, thickness: f32| {
+        let x = viewstate.cell_to_screen(Vec2Isize::new(col, 0)).x;
+        FRect::new(x.round() - (thickness / 2.0).floor(), 0.0, thickness, height)
+    };
+    let horizontal = |row: isize, thickness: f32| {
+        let y = viewstate.cell_to_screen(Vec2Isize::new(0, row)).y;
+        FRect::new(0.0, y.round() - (thickness / 2.0).floor(), width, thickness)
+    };
+
+    let major = grid_lines.major_every as isize;
+    let show_major = major > 0 && zoom * major as f32 >= grid_lines.min_zoom;
+    let is_major = |index: isize| show_major && index.rem_euclid(major) == 0;
+
+    canvas.set_blend_mode(BlendMode::Blend);
+    if zoom >= grid_lines.min_zoom {
+        let minor: Vec<FRect> = (first_col..=last_col)
+            .filter(|col| !is_major(*col))
+            .map(|col| vertical(col, 1.0))
+            .chain(
+                (first_row..=last_row)
+                    .filter(|row| !is_major(*row))
+                    .map(|row| horizontal(row, 1.0)),
+            )
+            .collect();
+        canvas.set_draw_color(theme.grid_line);
+        canvas.fill_rects(&minor).unwrap();
+    }
+    if show_major {
+        let majors: Vec<FRect> = multiples_between(first_col, last_col, major)
+            .map(|col| vertical(col, MAJOR_LINE_WIDTH))
+            .chain(
+                multiples_between(first_row, last_row, major)
+                    .map(|row| horizontal(row, MAJOR_LINE_WIDTH)),
+            )
+            .collect();
+        canvas.set_draw_color(theme.grid_major);
+        canvas.fill_rects(&majors).unwrap();
+    }
+    if grid_lines.axes {
+        let mut axes = Vec::new();
+        if (first_col..=last_col).contains(&0) {
+            axes.push(vertical(0, MAJOR_LINE_WIDTH));
+        }
+        if (first_row..=last_row).contains(&0) {
+            axes.push(horizontal(0, MAJOR_LINE_WIDTH));
+        }
+        canvas.set_draw_color(theme.axis);
+        canvas.fill_rects(&axes).unwrap();
+    }
+}
+
+/// Achieved and target rate, and the step size when it's more than one generation.
+fn gps_line(render_ctx: &RenderCtx) -> String {
+    let pacing = render_ctx.pacing;
+    let mut line = format!("{:.1}/{} gen/s", render_ctx.achieved_gps, pacing.rate);
+    if pacing.step_exponent > 0 {
+        line.push_str(&format!(" step 2^{}", pacing.step_exponent));
+    }
+    line
+}
+
+/// Outlines the cells at `footprint` offsets from the cursor, coloured by whether the cell under
+/// the cursor is alive.
+fn draw_selection(
+    canvas: &mut Canvas<Window>,
+    grid: &Grid,
+    footprint: &[Vec2Isize],
+    viewstate: ViewState,
+    theme: &Theme,
+) {
+    let cell = viewstate.cursor_cell();
+    if grid.get_cell(cell.x, cell.y) {
+        canvas.set_draw_color(theme.hover_alive);
+    } else {
+        canvas.set_draw_color(theme.hover_dead);
+    }
+
+    let outlines: Vec<FRect> = footprint
+        .iter()
+        .map(|offset| {
+            let corner =
+                viewstate.cell_to_screen(Vec2Isize::new(cell.x + offset.x, cell.y + offset.y));
             FRect {
-                x,
-                y,
-                w: (font_size / 2.0) * text.len() as f32,
-                h: font_size,
-            },
-        )
+                x: corner.x,
+                y: corner.y,
+                w: viewstate.zoom,
+                h: viewstate.zoom,
+            }
+        })
+        .collect();
+    canvas.draw_rects(&outlines).unwrap();
+}
+
+/// Outlines the selected rectangle, given by its inclusive corner cells.
+fn draw_marquee(
+    canvas: &mut Canvas<Window>,
+    (min, max): (Vec2Isize, Vec2Isize),
+    viewstate: ViewState,
+    theme: &Theme,
+) {
+    let top_left = viewstate.cell_to_screen(min);
+    let bottom_right = viewstate.cell_to_screen(Vec2Isize::new(max.x + 1, max.y + 1));
+    canvas.set_draw_color(theme.viewport);
+    canvas
+        .draw_rect(FRect::new(
+            top_left.x,
+            top_left.y,
+            bottom_right.x - top_left.x,
+            bottom_right.y - top_left.y,
+        ))
         .unwrap();
 }
 
-fn draw_cells(grid: &mut Grid, canvas: &mut Canvas<Window>, viewstate: ViewState) {
-    canvas.set_draw_color(Color::RGB(255, 255, 255));
-    for cell in grid.get_grid() {
-        if viewstate.zoom <= 1.0 {
-            canvas
-                .draw_point(FPoint {
-                    x: cell.y as f32 * viewstate.zoom + viewstate.camera_pos.x,
-                    y: cell.x as f32 * viewstate.zoom + viewstate.camera_pos.y,
-                })
-                .unwrap();
+/// Outlines the cells a tool is about to draw or erase.
+fn draw_tool_preview(
+    canvas: &mut Canvas<Window>,
+    cells: &[Vec2Isize],
+    draw: bool,
+    viewstate: ViewState,
+    theme: &Theme,
+) {
+    canvas.set_draw_color(if draw { theme.stamp } else { theme.hover_alive });
+    let size = viewstate.zoom.max(1.0);
+    let outlines: Vec<FRect> = cells
+        .iter()
+        .map(|&cell| {
+            let corner = viewstate.cell_to_screen(cell);
+            FRect::new(corner.x, corner.y, size, size)
+        })
+        .collect();
+    canvas.draw_rects(&outlines).unwrap();
+}
+
+fn draw_stamp_preview(
+    canvas: &mut Canvas<Window>,
+    stamp: &Grid,
+    origin: Vec2Isize,
+    viewstate: ViewState,
+    theme: &Theme,
+) {
+    canvas.set_draw_color(theme.stamp);
+    for cell in stamp.iter() {
+        let corner = viewstate.cell_to_screen(Vec2Isize::new(cell.x + origin.x, cell.y + origin.y));
+        canvas
+            .draw_rect(FRect {
+                x: corner.x,
+                y: corner.y,
+                w: viewstate.zoom.max(1.0),
+                h: viewstate.zoom.max(1.0),
+            })
+            .unwrap();
+    }
+}
+
+const GOTO_WIDTH: f32 = 360.0;
+const RANDOM_FILL_WIDTH: f32 = 480.0;
+
+/// A one-line text prompt centred at the top of the window, with an optional note underneath.
+fn draw_prompt(
+    text: &mut TextRenderer,
+    canvas: &mut Canvas<Window>,
+    prompt: &str,
+    note: Option<&str>,
+    panel_width: f32,
+    theme: &Theme,
+) {
+    let (width, _) = canvas.output_size().unwrap();
+    let panel_x = (width as f32 - panel_width) / 2.0;
+    let height = if note.is_some() { 72.0 } else { 44.0 };
+
+    canvas.set_draw_color(theme.panel);
+    canvas.fill_rect(FRect::new(panel_x, 40.0, panel_width, height)).unwrap();
+
+    text.draw(
+        canvas,
+        prompt,
+        panel_x + 8.0,
+        50.0,
+        TextStyle::new(20.0, theme.panel_text),
+    );
+    if let Some(note) = note {
+        text.draw(
+            canvas,
+            note,
+            panel_x + 8.0,
+            80.0,
+            TextStyle::new(16.0, theme.panel_muted_text),
+        );
+    }
+}
+
+fn draw_goto(
+    text: &mut TextRenderer,
+    canvas: &mut Canvas<Window>,
+    goto: &GotoDialog,
+    theme: &Theme,
+) {
+    let prompt = format!("go to x, y: {}_", goto.input);
+    draw_prompt(text, canvas, &prompt, goto.error.as_deref(), GOTO_WIDTH, theme);
+}
+
+fn draw_random_fill(
+    text: &mut TextRenderer,
+    canvas: &mut Canvas<Window>,
+    dialog: &RandomFillDialog,
+    theme: &Theme,
+) {
+    let prompt = format!("soup: {}_", dialog.input);
+    let hint = match dialog.last {
+        Some(_) => "size, density %, symmetry, seed; Enter alone rerolls the last soup",
+        None => "size, density %, symmetry, seed, e.g. 32x32 40% D4",
+    };
+    let note = dialog.error.as_deref().unwrap_or(hint);
+    draw_prompt(text, canvas, &prompt, Some(note), RANDOM_FILL_WIDTH, theme);
+}
+
+const BOOKMARK_ROWS: usize = 10;
+const BOOKMARK_ROW_HEIGHT: f32 = 26.0;
+
+fn draw_bookmarks(
+    text: &mut TextRenderer,
+    canvas: &mut Canvas<Window>,
+    dialog: &BookmarkDialog,
+    bookmarks: &BTreeMap<String, View>,
+    theme: &Theme,
+) {
+    let (width, _) = canvas.output_size().unwrap();
+    let panel_x = (width as f32 - GOTO_WIDTH) / 2.0;
+    let matches = dialog.matches(bookmarks);
+    let rows = matches.len().clamp(1, BOOKMARK_ROWS);
+    let list_y = 80.0;
+
+    canvas.set_draw_color(theme.panel);
+    canvas
+        .fill_rect(FRect::new(
+            panel_x,
+            40.0,
+            GOTO_WIDTH,
+            list_y - 40.0 + rows as f32 * BOOKMARK_ROW_HEIGHT + 8.0,
+        ))
+        .unwrap();
+
+    text.draw(
+        canvas,
+        &format!("bookmark: {}_", dialog.input),
+        panel_x + 8.0,
+        50.0,
+        TextStyle::new(20.0, theme.panel_text),
+    );
+
+    if matches.is_empty() {
+        let hint = if dialog.input.trim().is_empty() {
+            "type a name to save this view"
         } else {
+            "enter saves this view"
+        };
+        text.draw(
+            canvas,
+            hint,
+            panel_x + 8.0,
+            list_y,
+            TextStyle::new(16.0, theme.panel_muted_text),
+        );
+        return;
+    }
+
+    let first = dialog.selected.saturating_sub(BOOKMARK_ROWS - 1);
+    for (slot, name) in matches.iter().skip(first).take(BOOKMARK_ROWS).enumerate() {
+        let row_y = list_y + slot as f32 * BOOKMARK_ROW_HEIGHT;
+        if first + slot == dialog.selected {
+            canvas.set_draw_color(theme.panel_selected);
             canvas
-                .fill_rect(FRect {
-                    x: cell.y as f32 * viewstate.zoom + viewstate.camera_pos.x,
-                    y: cell.x as f32 * viewstate.zoom + viewstate.camera_pos.y,
-                    w: viewstate.zoom,
-                    h: viewstate.zoom,
-                })
+                .fill_rect(FRect::new(panel_x, row_y - 3.0, GOTO_WIDTH, BOOKMARK_ROW_HEIGHT))
                 .unwrap();
         }
+        let view = bookmarks[*name];
+        text.draw(
+            canvas,
+            name,
+            panel_x + 8.0,
+            row_y,
+            TextStyle::new(18.0, theme.panel_text),
+        );
+        text.draw(
+            canvas,
+            &format!("{:.0}, {:.0} at {:.3}x", view.x, view.y, view.zoom),
+            panel_x + GOTO_WIDTH - 8.0,
+            row_y + 2.0,
+            TextStyle::new(16.0, theme.panel_muted_text).aligned(Align::Right),
+        );
     }
 }
 
-fn draw_selection(canvas: &mut Canvas<Window>, grid: &Grid, viewstate: ViewState) {
-    let (mouse_x, mouse_y, scale, cam_x, cam_y) = (
-        viewstate.mouse_pos.x,
-        viewstate.mouse_pos.y,
-        viewstate.zoom,
-        viewstate.camera_pos.x,
-        viewstate.camera_pos.y,
+const BROWSER_WIDTH: f32 = 340.0;
+const BROWSER_ROW_HEIGHT: f32 = 72.0;
+const THUMBNAIL_SIZE: f32 = 64.0;
+
+fn draw_browser(
+    text: &mut TextRenderer,
+    canvas: &mut Canvas<Window>,
+    browser: &PatternBrowser,
+    theme: &Theme,
+) {
+    let (width, height) = canvas.output_size().unwrap();
+    let panel_x = width as f32 - BROWSER_WIDTH;
+
+    canvas.set_draw_color(theme.panel);
+    canvas
+        .fill_rect(FRect {
+            x: panel_x,
+            y: 0.0,
+            w: BROWSER_WIDTH,
+            h: height as f32,
+        })
+        .unwrap();
+
+    text.draw(
+        canvas,
+        &format!("search: {}_", browser.query),
+        panel_x + 8.0,
+        8.0,
+        TextStyle::new(20.0, theme.panel_text),
     );
 
-    let world_x = mouse_x - cam_x;
-    let world_y = mouse_y - cam_y;
+    let matches = browser.matches();
+    if matches.is_empty() {
+        text.draw(
+            canvas,
+            "no matches",
+            panel_x + 8.0,
+            40.0,
+            TextStyle::new(20.0, theme.panel_muted_text),
+        );
+        return;
+    }
 
-    let select_world_x = round_down_to_multiple(world_x, scale);
-    let select_world_y = round_down_to_multiple(world_y, scale);
+    let visible_rows = (((height as f32 - 40.0) / BROWSER_ROW_HEIGHT) as usize).max(1);
+    let first = browser.selected.saturating_sub(visible_rows - 1);
 
-    let x = (select_world_y / scale) as isize;
-    let y = (select_world_x / scale) as isize;
+    for (slot, &index) in matches.iter().skip(first).take(visible_rows).enumerate() {
+        let pattern = &browser.patterns[index];
+        let row_y = 40.0 + slot as f32 * BROWSER_ROW_HEIGHT;
 
-    if grid.get_cell(x, y) {
-        canvas.set_draw_color(Color::RGB(255, 0, 0));
-    } else {
-        canvas.set_draw_color(Color::RGB(0, 255, 0));
+        if first + slot == browser.selected {
+            canvas.set_draw_color(theme.panel_selected);
+            canvas
+                .fill_rect(FRect {
+                    x: panel_x,
+                    y: row_y,
+                    w: BROWSER_WIDTH,
+                    h: BROWSER_ROW_HEIGHT,
+                })
+                .unwrap();
+        }
+
+        draw_thumbnail(
+            canvas,
+            &pattern.grid,
+            theme,
+            FRect {
+                x: panel_x + 4.0,
+                y: row_y + 4.0,
+                w: THUMBNAIL_SIZE,
+                h: THUMBNAIL_SIZE,
+            },
+        );
+        text.draw(
+            canvas,
+            &pattern.name,
+            panel_x + THUMBNAIL_SIZE + 12.0,
+            row_y + 12.0,
+            TextStyle::new(20.0, theme.panel_text),
+        );
+        text.draw(
+            canvas,
+            pattern.category.name(),
+            panel_x + BROWSER_WIDTH - 8.0,
+            row_y + 44.0,
+            TextStyle::new(16.0, theme.panel_muted_text).aligned(Align::Right),
+        );
     }
+}
 
-    let screen_x = select_world_x + cam_x;
-    let screen_y = select_world_y + cam_y;
+fn draw_thumbnail(canvas: &mut Canvas<Window>, grid: &Grid, theme: &Theme, area: FRect) {
+    canvas.set_draw_color(theme.background);
+    canvas.fill_rect(area).unwrap();
 
-    canvas
-        .draw_rect(FRect {
-            x: screen_x,
-            y: screen_y,
-            w: scale,
-            h: scale,
-        })
-        .unwrap();
+    let Some((min, max)) = grid.bounds() else {
+        return;
+    };
+    let cols = (max.x - min.x + 1) as f32;
+    let rows = (max.y - min.y + 1) as f32;
+    let scale = (area.w / cols).min(area.h / rows).min(8.0);
+    let offset_x = area.x + (area.w - cols * scale) / 2.0;
+    let offset_y = area.y + (area.h - rows * scale) / 2.0;
+
+    canvas.set_draw_color(theme.cell);
+    for cell in grid.iter() {
+        canvas
+            .fill_rect(FRect {
+                x: offset_x + (cell.x - min.x) as f32 * scale,
+                y: offset_y + (cell.y - min.y) as f32 * scale,
+                w: scale.max(1.0),
+                h: scale.max(1.0),
+            })
+            .unwrap();
+    }
 }
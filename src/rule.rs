@@ -0,0 +1,96 @@
+use std::fmt;
+use std::str::FromStr;
+
+/// A Life-like outer totalistic rule, e.g. `B3/S23` for Conway's Game of Life.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rule {
+    pub birth: [bool; 9],
+    pub survival: [bool; 9],
+}
+
+impl Rule {
+    pub fn next_state(&self, alive: bool, neighbours: u8) -> bool {
+        if alive {
+            self.survival[neighbours as usize]
+        } else {
+            self.birth[neighbours as usize]
+        }
+    }
+}
+
+impl Default for Rule {
+    fn default() -> Self {
+        "B3/S23".parse().unwrap()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuleParseError {
+    pub rule: String,
+    pub reason: &'static str,
+}
+
+impl fmt::Display for RuleParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid rule `{}`: {}", self.rule, self.reason)
+    }
+}
+
+impl std::error::Error for RuleParseError {}
+
+fn parse_counts(digits: &str) -> Option<[bool; 9]> {
+    let mut counts = [false; 9];
+    for ch in digits.chars() {
+        let count = ch.to_digit(10).filter(|&n| n <= 8)?;
+        counts[count as usize] = true;
+    }
+    Some(counts)
+}
+
+impl FromStr for Rule {
+    type Err = RuleParseError;
+
+    /// Accepts `B3/S23`, `b3s23` and the older survival-first `23/3` notation.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || RuleParseError {
+            rule: s.to_string(),
+            reason: "expected B/S notation like B3/S23",
+        };
+        let rule = s.trim().to_ascii_uppercase();
+
+        let (birth, survival) = if let Some(rest) = rule.strip_prefix('B') {
+            let (birth, survival) = rest.split_once('S').ok_or_else(error)?;
+            (birth.trim_end_matches('/'), survival)
+        } else {
+            let (survival, birth) = rule.split_once('/').ok_or_else(error)?;
+            (birth, survival)
+        };
+
+        let birth = parse_counts(birth).ok_or_else(error)?;
+        if birth[0] {
+            // A B0 rule turns the infinite empty background on, which a sparse set of live
+            // cells cannot represent.
+            return Err(RuleParseError {
+                rule: s.to_string(),
+                reason: "rules with B0 are not supported",
+            });
+        }
+
+        Ok(Rule {
+            birth,
+            survival: parse_counts(survival).ok_or_else(error)?,
+        })
+    }
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let digits = |counts: &[bool; 9]| -> String {
+            (0..9)
+                .filter(|&n| counts[n])
+                .map(|n| char::from(b'0' + n as u8))
+                .collect()
+        };
+        write!(f, "B{}/S{}", digits(&self.birth), digits(&self.survival))
+    }
+}
@@ -0,0 +1,83 @@
+use crate::bookmarks::View;
+use log::warn;
+use rust_sdl_gol::camera::{MAX_ZOOM, MIN_ZOOM, ViewState};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// State kept between runs that the user builds up while exploring rather than configures.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Session {
+    /// Saved views by name. The number keys save to and recall the names `0` to `9`.
+    pub bookmarks: BTreeMap<String, View>,
+}
+
+impl Session {
+    /// `$XDG_DATA_HOME/rust_sdl_gol/session.toml`, or the platform equivalent.
+    pub fn path() -> Option<PathBuf> {
+        dirs::data_dir().map(|dir| dir.join("rust_sdl_gol").join("session.toml"))
+    }
+
+    /// Reads the session at `path`, returning an empty one if the file doesn't exist yet.
+    pub fn load(path: &Path) -> Result<Session, String> {
+        match fs::read_to_string(path) {
+            Ok(text) => {
+                let mut session: Session = toml::from_str(&text).map_err(|e| e.to_string())?;
+                session.validate();
+                Ok(session)
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Session::default()),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+
+    /// Brings bookmark zooms the camera can't use into its range, using the default zoom for
+    /// ones that aren't a positive number.
+    fn validate(&mut self) {
+        for (name, view) in &mut self.bookmarks {
+            let zoom = if view.zoom.is_nan() || view.zoom <= 0.0 {
+                ViewState::default().zoom
+            } else {
+                view.zoom.clamp(MIN_ZOOM, MAX_ZOOM)
+            };
+            if zoom != view.zoom {
+                warn!("Bookmark {} has zoom {}, using {}", name, view.zoom, zoom);
+                view.zoom = zoom;
+            }
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let text = toml::to_string_pretty(self).map_err(|e| e.to_string())?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        fs::write(path, text).map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unusable_bookmark_zooms_are_replaced() {
+        let mut session: Session = toml::from_str(
+            "[bookmarks]\n\
+             zero = { x = 1.0, y = 2.0, zoom = 0.0 }\n\
+             negative = { x = 0.0, y = 0.0, zoom = -3.0 }\n\
+             huge = { x = 0.0, y = 0.0, zoom = 1e9 }\n\
+             fine = { x = 0.0, y = 0.0, zoom = 2.5 }\n",
+        )
+        .unwrap();
+        session.validate();
+        let zoom = |name: &str| session.bookmarks[name].zoom;
+        assert_eq!(zoom("zero"), ViewState::default().zoom);
+        assert_eq!(zoom("negative"), ViewState::default().zoom);
+        assert_eq!(zoom("huge"), MAX_ZOOM);
+        assert_eq!(zoom("fine"), 2.5);
+        assert_eq!(session.bookmarks["zero"].x, 1.0);
+    }
+}
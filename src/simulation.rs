@@ -0,0 +1,187 @@
+use crate::pacing::Pacing;
+use rust_sdl_gol::gol::{Engine, GOL, Grid};
+use rust_sdl_gol::rule::Rule;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Instant;
+
+enum Command {
+    /// Carry on from this grid, which the UI has edited. Tagged with the epoch it starts.
+    Load(Arc<Grid>, u64),
+    Run(bool),
+    Pacing(Pacing),
+    /// Advance this many generations now and then pause.
+    Step(u64),
+}
+
+/// A finished generation waiting for the UI.
+struct Frame {
+    grid: Arc<Grid>,
+    /// Generations since the previous frame the UI took.
+    generations: u64,
+    /// Epoch of the grid the frame was computed from.
+    epoch: u64,
+}
+
+/// The simulation thread's state.
+struct Worker {
+    grid: Arc<Grid>,
+    rule: Rule,
+    engine: Engine,
+    epoch: u64,
+    running: bool,
+    pacing: Pacing,
+    latest: Arc<Mutex<Option<Frame>>>,
+}
+
+impl Worker {
+    fn run(mut self, commands: Receiver<Command>) {
+        // When the next update is due, kept on a fixed schedule so the rate doesn't drift.
+        let mut next_update = Instant::now();
+        loop {
+            let command = if self.running {
+                let wait = next_update.saturating_duration_since(Instant::now());
+                match commands.recv_timeout(wait) {
+                    Ok(command) => Some(command),
+                    Err(RecvTimeoutError::Timeout) => None,
+                    Err(RecvTimeoutError::Disconnected) => return,
+                }
+            } else {
+                match commands.recv() {
+                    Ok(command) => Some(command),
+                    Err(_) => return,
+                }
+            };
+
+            match command {
+                Some(Command::Load(grid, epoch)) => {
+                    self.grid = grid;
+                    self.epoch = epoch;
+                }
+                Some(Command::Run(running)) => {
+                    if running && !self.running {
+                        next_update = Instant::now();
+                    }
+                    self.running = running;
+                }
+                Some(Command::Pacing(pacing)) => self.pacing = pacing,
+                Some(Command::Step(generations)) => {
+                    self.running = false;
+                    self.advance(generations);
+                }
+                None => {
+                    let now = Instant::now();
+                    self.advance(self.pacing.step_size());
+                    next_update = match self.pacing.update_interval() {
+                        // Start afresh rather than rushing to catch up after falling behind.
+                        Some(interval) if now - next_update < interval => next_update + interval,
+                        Some(interval) => now + interval,
+                        None => now,
+                    };
+                }
+            }
+        }
+    }
+
+    fn advance(&mut self, generations: u64) {
+        let next = GOL::update_by(&self.grid, &self.rule, self.engine, generations);
+        self.grid = Arc::new(next);
+        let mut latest = self.latest.lock().unwrap();
+        // Frames the UI hasn't taken yet still count towards the generation total.
+        let skipped = match latest.take() {
+            Some(frame) if frame.epoch == self.epoch => frame.generations,
+            _ => 0,
+        };
+        *latest = Some(Frame {
+            grid: self.grid.clone(),
+            generations: skipped + generations,
+            epoch: self.epoch,
+        });
+    }
+}
+
+/// Runs the simulation on its own thread, at its own pace. The UI tells it about edits and
+/// setting changes with `sync` and picks up the latest finished generation with `poll`, neither
+/// of which waits for a generation to finish.
+///
+/// Generations pass between the threads as shared `Arc<Grid>`s, so neither handing one over
+/// nor drawing it copies the cells. Only editing a grid the simulation still holds copies it.
+pub struct Simulation {
+    commands: Sender<Command>,
+    latest: Arc<Mutex<Option<Frame>>>,
+    /// Bumped whenever the UI edits the grid, so generations computed from the old grid are
+    /// thrown away.
+    epoch: u64,
+    /// Version of the UI's grid the simulation last agreed with.
+    synced_version: u64,
+    running: bool,
+    pacing: Pacing,
+}
+
+impl Simulation {
+    pub fn spawn(gol: &GOL, pacing: Pacing) -> Simulation {
+        let (commands, receiver) = mpsc::channel();
+        let latest = Arc::new(Mutex::new(None));
+        let worker = Worker {
+            grid: gol.grid.clone(),
+            rule: gol.rule,
+            engine: gol.engine,
+            epoch: 0,
+            running: !gol.paused,
+            pacing,
+            latest: latest.clone(),
+        };
+        thread::spawn(move || worker.run(receiver));
+        Simulation {
+            commands,
+            latest,
+            epoch: 0,
+            synced_version: gol.grid.version(),
+            running: !gol.paused,
+            pacing,
+        }
+    }
+
+    /// Passes on whatever the UI changed since the last call: edits to the grid, pausing and
+    /// the pacing.
+    pub fn sync(&mut self, gol: &GOL, pacing: Pacing) {
+        // A closed channel means the thread died, which it only does when we drop the sender.
+        if gol.grid.version() != self.synced_version {
+            self.epoch += 1;
+            self.synced_version = gol.grid.version();
+            let _ = self
+                .commands
+                .send(Command::Load(gol.grid.clone(), self.epoch));
+        }
+        if gol.paused == self.running {
+            self.running = !gol.paused;
+            let _ = self.commands.send(Command::Run(self.running));
+        }
+        if pacing != self.pacing {
+            self.pacing = pacing;
+            let _ = self.commands.send(Command::Pacing(pacing));
+        }
+    }
+
+    /// Advances `generations` as soon as the simulation gets to it, then pauses. Call after
+    /// `sync` with the simulation paused.
+    pub fn step(&mut self, generations: u64) {
+        self.running = false;
+        let _ = self.commands.send(Command::Step(generations));
+    }
+
+    /// Moves `gol` on to the latest finished generation, if there's one newer than the UI's
+    /// grid. Returns how many generations it advanced.
+    pub fn poll(&mut self, gol: &mut GOL) -> u64 {
+        let Some(frame) = self.latest.lock().unwrap().take() else {
+            return 0;
+        };
+        if frame.epoch != self.epoch {
+            return 0;
+        }
+        gol.advance_by(frame.grid, frame.generations);
+        self.synced_version = gol.grid.version();
+        frame.generations
+    }
+}
@@ -0,0 +1,183 @@
+//! Random soups: rectangles of random cells at a given density, optionally symmetric, generated
+//! from a seed so the same seed always gives the same soup.
+
+use crate::gol::{Grid, Vec2Isize};
+use std::fmt;
+use std::str::FromStr;
+
+/// Symmetries a soup can be forced to have, named as in apgsearch. Rotations and reflections are
+/// about the centre of the soup's rectangle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Symmetry {
+    /// No symmetry.
+    #[default]
+    C1,
+    /// Unchanged by a half turn.
+    C2,
+    /// Unchanged by a quarter turn. Only square soups can have it.
+    C4,
+    /// Mirrored left to right.
+    D2,
+    /// Mirrored left to right and top to bottom.
+    D4,
+    /// Unchanged by every rotation and reflection of the square. Only square soups can have it.
+    D8,
+}
+
+impl Symmetry {
+    pub const ALL: [Symmetry; 6] = [
+        Symmetry::C1,
+        Symmetry::C2,
+        Symmetry::C4,
+        Symmetry::D2,
+        Symmetry::D4,
+        Symmetry::D8,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Symmetry::C1 => "C1",
+            Symmetry::C2 => "C2",
+            Symmetry::C4 => "C4",
+            Symmetry::D2 => "D2",
+            Symmetry::D4 => "D4",
+            Symmetry::D8 => "D8",
+        }
+    }
+
+    /// Whether the symmetry turns rows into columns, so only fits a square.
+    pub fn needs_square(self) -> bool {
+        matches!(self, Symmetry::C4 | Symmetry::D8)
+    }
+
+    /// Where the symmetry's transformations take the point `(u, v)`, measured from the centre
+    /// of the rectangle.
+    fn images(self, (u, v): (i64, i64)) -> Vec<(i64, i64)> {
+        match self {
+            Symmetry::C1 => vec![(u, v)],
+            Symmetry::C2 => vec![(u, v), (-u, -v)],
+            Symmetry::C4 => vec![(u, v), (-v, u), (-u, -v), (v, -u)],
+            Symmetry::D2 => vec![(u, v), (-u, v)],
+            Symmetry::D4 => vec![(u, v), (-u, v), (u, -v), (-u, -v)],
+            Symmetry::D8 => vec![
+                (u, v),
+                (-u, v),
+                (u, -v),
+                (-u, -v),
+                (v, u),
+                (-v, u),
+                (v, -u),
+                (-v, -u),
+            ],
+        }
+    }
+}
+
+impl fmt::Display for Symmetry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Symmetry {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Symmetry::ALL
+            .into_iter()
+            .find(|symmetry| symmetry.name().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| {
+                format!(
+                    "unknown symmetry `{}`, expected C1, C2, C4, D2, D4 or D8",
+                    s.trim()
+                )
+            })
+    }
+}
+
+/// A recipe for a random soup.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Soup {
+    pub width: usize,
+    pub height: usize,
+    /// Share of cells that are alive, from 0 to 1.
+    pub density: f64,
+    pub symmetry: Symmetry,
+    pub seed: u64,
+}
+
+impl Soup {
+    /// The soup's live cells, with the rectangle's top-left corner at the origin.
+    ///
+    /// Each cell's state comes from hashing the seed with the cell's orbit under the symmetry,
+    /// so cells that the symmetry maps onto each other always agree, and a soup doesn't depend
+    /// on the order cells are visited in or on any random number library's version.
+    pub fn generate(&self) -> Grid {
+        let (width, height) = (self.width as i64, self.height as i64);
+        let mut grid = Grid::new();
+        for y in 0..height {
+            for x in 0..width {
+                // Doubled so the centre of an even-sized rectangle lands on whole numbers.
+                let point = (2 * x - (width - 1), 2 * y - (height - 1));
+                let orbit = self.symmetry.images(point).into_iter().min().unwrap();
+                if self.alive(orbit) {
+                    grid.set_cell(x as isize, y as isize, true);
+                }
+            }
+        }
+        grid
+    }
+
+    fn alive(&self, (u, v): (i64, i64)) -> bool {
+        let hash = splitmix64(splitmix64(splitmix64(self.seed) ^ u as u64) ^ v as u64);
+        // The top 53 bits, as a fraction of one.
+        let sample = (hash >> 11) as f64 / (1u64 << 53) as f64;
+        sample < self.density
+    }
+
+    /// Replaces the cells in the soup's rectangle, with its top-left corner at `origin`, by the
+    /// soup.
+    pub fn fill(&self, grid: &mut Grid, origin: Vec2Isize) {
+        for y in 0..self.height as isize {
+            for x in 0..self.width as isize {
+                grid.set_cell(origin.x + x, origin.y + y, false);
+            }
+        }
+        grid.paste(&self.generate(), origin.x, origin.y);
+    }
+}
+
+impl fmt::Display for Soup {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}x{} {}% {} {}",
+            self.width,
+            self.height,
+            percentage(self.density),
+            self.symmetry,
+            self.seed
+        )
+    }
+}
+
+/// `density` as the shortest percentage that reads back as the same density, so `0.07` shows as
+/// `7` rather than `7.000000000000001`.
+fn percentage(density: f64) -> String {
+    let percent = density * 100.0;
+    (0..=17)
+        .map(|precision| format!("{:.*}", precision, percent))
+        .find(|text| {
+            text.parse::<f64>()
+                .is_ok_and(|value| value / 100.0 == density)
+        })
+        .unwrap_or_else(|| percent.to_string())
+}
+
+/// Sebastiano Vigna's SplitMix64 finaliser, a fast hash whose output looks uniformly random.
+fn splitmix64(x: u64) -> u64 {
+    let mut z = x.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
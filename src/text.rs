@@ -0,0 +1,289 @@
+use crate::bitmap_font::{GLYPH_HEIGHT, GLYPH_WIDTH, glyph};
+use crate::font::FontSource;
+use log::warn;
+use sdl3::pixels::Color;
+use sdl3::render::{Canvas, FRect, Texture, TextureCreator};
+use sdl3::ttf::{Font, Sdl3TtfContext};
+use sdl3::video::{Window, WindowContext};
+use std::collections::HashMap;
+
+/// Characters rasterised into each atlas; anything else is drawn as `?`.
+const FIRST_GLYPH: char = ' ';
+const LAST_GLYPH: char = '~';
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Align {
+    Left,
+    Center,
+    Right,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TextStyle {
+    pub size: f32,
+    pub color: Color,
+    pub align: Align,
+}
+
+impl TextStyle {
+    pub fn new(size: f32, color: Color) -> Self {
+        TextStyle {
+            size,
+            color,
+            align: Align::Left,
+        }
+    }
+
+    pub fn aligned(self, align: Align) -> Self {
+        TextStyle { align, ..self }
+    }
+}
+
+struct Glyph {
+    /// Source rectangle in the atlas texture; its width is the glyph's advance.
+    rect: FRect,
+}
+
+/// Every printable ASCII glyph for one font size, rendered once into a single white texture
+/// that is tinted per draw.
+struct Atlas<'a> {
+    font: Font<'static>,
+    texture: Texture<'a>,
+    glyphs: HashMap<char, Glyph>,
+    kerning: HashMap<(char, char), f32>,
+    line_height: f32,
+}
+
+impl<'a> Atlas<'a> {
+    fn build(
+        texture_creator: &'a TextureCreator<WindowContext>,
+        font: Font<'static>,
+    ) -> Result<Atlas<'a>, String> {
+        let charset: String = (FIRST_GLYPH..=LAST_GLYPH).collect();
+        let surface = font
+            .render(&charset)
+            .blended(Color::RGB(255, 255, 255))
+            .map_err(|e| e.to_string())?;
+        let texture = texture_creator
+            .create_texture_from_surface(surface)
+            .map_err(|e| e.to_string())?;
+
+        // Measuring each prefix of the rendered string gives every glyph's offset in the strip,
+        // including any kerning the font applied between neighbours.
+        let mut glyphs = HashMap::new();
+        for (index, ch) in charset.char_indices() {
+            let (advance, height) = font.size_of_char(ch).map_err(|e| e.to_string())?;
+            let (prefix_width, _) = font
+                .size_of(&charset[..index + ch.len_utf8()])
+                .map_err(|e| e.to_string())?;
+            let rect = FRect {
+                x: prefix_width as f32 - advance as f32,
+                y: 0.0,
+                w: advance as f32,
+                h: height as f32,
+            };
+            glyphs.insert(ch, Glyph { rect });
+        }
+
+        Ok(Atlas {
+            line_height: font.height() as f32,
+            font,
+            texture,
+            glyphs,
+            kerning: HashMap::new(),
+        })
+    }
+
+    fn glyph(&self, ch: char) -> &Glyph {
+        self.glyphs.get(&ch).unwrap_or_else(|| &self.glyphs[&'?'])
+    }
+
+    fn kerning(&mut self, previous: char, ch: char) -> f32 {
+        let key = (previous, ch);
+        if let Some(&kerning) = self.kerning.get(&key) {
+            return kerning;
+        }
+        let pair: String = [previous, ch].iter().collect();
+        let kerning = match self.font.size_of(&pair) {
+            Ok((width, _)) => width as f32 - self.glyph(previous).rect.w - self.glyph(ch).rect.w,
+            Err(_) => 0.0,
+        };
+        self.kerning.insert(key, kerning);
+        kerning
+    }
+
+    fn line_width(&mut self, line: &str) -> f32 {
+        let mut width = 0.0;
+        let mut previous = None;
+        for ch in line.chars() {
+            if let Some(previous) = previous {
+                width += self.kerning(previous, ch);
+            }
+            width += self.glyph(ch).rect.w;
+            previous = Some(ch);
+        }
+        width
+    }
+}
+
+/// Draws and measures HUD text. TTF fonts get a glyph atlas per pixel size, built on first use;
+/// without TTF the built-in bitmap font is scaled instead.
+pub struct TextRenderer<'a> {
+    texture_creator: &'a TextureCreator<WindowContext>,
+    ttf_context: Option<&'a Sdl3TtfContext>,
+    source: FontSource,
+    /// `None` marks a size whose atlas couldn't be built, so the bitmap font is used for it.
+    atlases: HashMap<u32, Option<Atlas<'a>>>,
+}
+
+impl<'a> TextRenderer<'a> {
+    pub fn new(
+        texture_creator: &'a TextureCreator<WindowContext>,
+        ttf_context: Option<&'a Sdl3TtfContext>,
+        source: FontSource,
+    ) -> Self {
+        TextRenderer {
+            texture_creator,
+            ttf_context,
+            source,
+            atlases: HashMap::new(),
+        }
+    }
+
+    fn atlas(&mut self, size: f32) -> Option<&mut Atlas<'a>> {
+        let key = size.round().max(1.0) as u32;
+        let (texture_creator, ttf_context, source) =
+            (self.texture_creator, self.ttf_context, &self.source);
+
+        self.atlases
+            .entry(key)
+            .or_insert_with(|| {
+                let ttf_context = ttf_context?;
+                source
+                    .load(ttf_context, key as f32)
+                    .and_then(|font| Atlas::build(texture_creator, font))
+                    .inspect_err(|e| {
+                        if *source != FontSource::Bitmap {
+                            warn!("Couldn't build {}px glyph atlas: {}", key, e)
+                        }
+                    })
+                    .ok()
+            })
+            .as_mut()
+    }
+
+    fn bitmap_scale(size: f32) -> f32 {
+        (size / GLYPH_HEIGHT as f32).round().max(1.0)
+    }
+
+    /// Width of the widest line and total height of `text` at `size` pixels.
+    pub fn measure(&mut self, text: &str, size: f32) -> (f32, f32) {
+        let line_count = text.lines().count().max(1) as f32;
+        match self.atlas(size) {
+            Some(atlas) => {
+                let width = text
+                    .lines()
+                    .map(|line| atlas.line_width(line))
+                    .fold(0.0, f32::max);
+                (width, atlas.line_height * line_count)
+            }
+            None => {
+                let scale = Self::bitmap_scale(size);
+                let longest = text.lines().map(|line| line.chars().count()).max();
+                (
+                    (longest.unwrap_or(0) * GLYPH_WIDTH) as f32 * scale,
+                    GLYPH_HEIGHT as f32 * scale * line_count,
+                )
+            }
+        }
+    }
+
+    /// Draws `text` with its top edge at `y`. `x` is the left edge, centre or right edge of each
+    /// line depending on the style's alignment.
+    pub fn draw(
+        &mut self,
+        canvas: &mut Canvas<Window>,
+        text: &str,
+        x: f32,
+        y: f32,
+        style: TextStyle,
+    ) {
+        match self.atlas(style.size) {
+            Some(atlas) => draw_atlas_text(canvas, atlas, text, x, y, style),
+            None => draw_bitmap_text(canvas, text, x, y, style),
+        }
+    }
+}
+
+fn aligned_x(x: f32, width: f32, align: Align) -> f32 {
+    match align {
+        Align::Left => x,
+        Align::Center => x - width / 2.0,
+        Align::Right => x - width,
+    }
+}
+
+fn draw_atlas_text(
+    canvas: &mut Canvas<Window>,
+    atlas: &mut Atlas,
+    text: &str,
+    x: f32,
+    y: f32,
+    style: TextStyle,
+) {
+    let (r, g, b, a) = style.color.rgba();
+    atlas.texture.set_color_mod(r, g, b);
+    atlas.texture.set_alpha_mod(a);
+
+    for (line_index, line) in text.lines().enumerate() {
+        let line_y = y + line_index as f32 * atlas.line_height;
+        let mut pen_x = aligned_x(x, atlas.line_width(line), style.align);
+        let mut previous = None;
+
+        for ch in line.chars() {
+            if let Some(previous) = previous {
+                pen_x += atlas.kerning(previous, ch);
+            }
+            let src = atlas.glyph(ch).rect;
+            let dst = FRect {
+                x: pen_x.round(),
+                y: line_y.round(),
+                w: src.w,
+                h: src.h,
+            };
+            canvas.copy(&atlas.texture, src, dst).unwrap();
+            pen_x += src.w;
+            previous = Some(ch);
+        }
+    }
+}
+
+fn draw_bitmap_text(canvas: &mut Canvas<Window>, text: &str, x: f32, y: f32, style: TextStyle) {
+    let scale = TextRenderer::bitmap_scale(style.size);
+    let mut pixels = Vec::new();
+
+    for (line_index, line) in text.lines().enumerate() {
+        let line_y = y + (line_index * GLYPH_HEIGHT) as f32 * scale;
+        let width = (line.chars().count() * GLYPH_WIDTH) as f32 * scale;
+        let line_x = aligned_x(x, width, style.align);
+
+        for (index, ch) in line.chars().enumerate() {
+            let glyph_x = line_x + (index * GLYPH_WIDTH) as f32 * scale;
+            for (row, bits) in glyph(ch).iter().enumerate() {
+                for col in 0..GLYPH_WIDTH {
+                    if bits & (0x80 >> col) != 0 {
+                        pixels.push(FRect {
+                            x: glyph_x + col as f32 * scale,
+                            y: line_y + row as f32 * scale,
+                            w: scale,
+                            h: scale,
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    canvas.set_draw_color(style.color);
+    canvas.fill_rects(&pixels).unwrap();
+}
@@ -0,0 +1,230 @@
+use sdl3::pixels::Color;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Theme {
+    pub background: Color,
+    pub cell: Color,
+    pub hover_alive: Color,
+    pub hover_dead: Color,
+    pub text: Color,
+    pub paused_text: Color,
+    /// Outline of a pattern waiting to be stamped.
+    pub stamp: Color,
+    pub grid_line: Color,
+    pub grid_major: Color,
+    /// Lines through the origin cell's edges.
+    pub axis: Color,
+    /// Outline of the visible area on the minimap.
+    pub viewport: Color,
+    pub panel: Color,
+    pub panel_selected: Color,
+    pub panel_text: Color,
+    pub panel_muted_text: Color,
+    /// Cells born in the latest generation, in the births colour mode.
+    pub birth: Color,
+    /// Gradient from newborn to long-lived cells.
+    pub age: Vec<Color>,
+    /// Gradient from settled cells to cells that keep toggling.
+    pub heat: Vec<Color>,
+    /// Gradient the cells left behind in the trails colour mode fade through, from just died to
+    /// about to disappear.
+    pub trail: Vec<Color>,
+}
+
+impl Theme {
+    /// Built-in theme names, as accepted by `Theme::named`.
+    pub const NAMES: [&'static str; 4] = ["dark", "light", "high-contrast", "colour-blind"];
+
+    pub fn dark() -> Theme {
+        Theme {
+            background: Color::RGB(0, 0, 0),
+            cell: Color::RGB(255, 255, 255),
+            hover_alive: Color::RGB(255, 0, 0),
+            hover_dead: Color::RGB(0, 255, 0),
+            text: Color::RGB(255, 255, 255),
+            paused_text: Color::RGB(255, 0, 0),
+            stamp: Color::RGB(0, 160, 255),
+            grid_line: Color::RGBA(255, 255, 255, 28),
+            grid_major: Color::RGBA(255, 255, 255, 64),
+            axis: Color::RGBA(0, 160, 255, 150),
+            viewport: Color::RGB(255, 200, 0),
+            panel: Color::RGB(24, 24, 32),
+            panel_selected: Color::RGB(60, 60, 90),
+            panel_text: Color::RGB(255, 255, 255),
+            panel_muted_text: Color::RGB(160, 160, 160),
+            birth: Color::RGB(80, 220, 100),
+            age: vec![
+                Color::RGB(255, 255, 255),
+                Color::RGB(255, 200, 60),
+                Color::RGB(230, 60, 40),
+                Color::RGB(120, 40, 160),
+            ],
+            heat: vec![
+                Color::RGB(40, 70, 200),
+                Color::RGB(220, 50, 50),
+                Color::RGB(255, 240, 80),
+            ],
+            trail: vec![
+                Color::RGB(180, 180, 220),
+                Color::RGB(90, 90, 150),
+                Color::RGB(40, 40, 80),
+            ],
+        }
+    }
+
+    pub fn light() -> Theme {
+        Theme {
+            background: Color::RGB(245, 245, 240),
+            cell: Color::RGB(20, 20, 20),
+            hover_alive: Color::RGB(200, 0, 0),
+            hover_dead: Color::RGB(0, 150, 0),
+            text: Color::RGB(20, 20, 20),
+            paused_text: Color::RGB(200, 0, 0),
+            stamp: Color::RGB(0, 110, 220),
+            grid_line: Color::RGBA(0, 0, 0, 24),
+            grid_major: Color::RGBA(0, 0, 0, 64),
+            axis: Color::RGBA(0, 90, 200, 150),
+            viewport: Color::RGB(220, 100, 0),
+            panel: Color::RGB(225, 225, 220),
+            panel_selected: Color::RGB(190, 200, 230),
+            panel_text: Color::RGB(20, 20, 20),
+            panel_muted_text: Color::RGB(100, 100, 100),
+            birth: Color::RGB(0, 150, 60),
+            age: vec![
+                Color::RGB(20, 20, 20),
+                Color::RGB(200, 120, 0),
+                Color::RGB(190, 30, 30),
+                Color::RGB(110, 30, 150),
+            ],
+            heat: vec![
+                Color::RGB(60, 90, 200),
+                Color::RGB(200, 40, 40),
+                Color::RGB(230, 180, 0),
+            ],
+            trail: vec![
+                Color::RGB(90, 90, 140),
+                Color::RGB(150, 150, 190),
+                Color::RGB(210, 210, 225),
+            ],
+        }
+    }
+
+    /// Pure colours on black, for low vision and bright rooms.
+    pub fn high_contrast() -> Theme {
+        Theme {
+            background: Color::RGB(0, 0, 0),
+            cell: Color::RGB(255, 255, 255),
+            hover_alive: Color::RGB(255, 255, 0),
+            hover_dead: Color::RGB(0, 255, 255),
+            text: Color::RGB(255, 255, 255),
+            paused_text: Color::RGB(255, 255, 0),
+            stamp: Color::RGB(0, 255, 255),
+            grid_line: Color::RGBA(255, 255, 255, 64),
+            grid_major: Color::RGBA(255, 255, 255, 128),
+            axis: Color::RGBA(0, 255, 255, 220),
+            viewport: Color::RGB(255, 255, 0),
+            panel: Color::RGB(0, 0, 0),
+            panel_selected: Color::RGB(0, 0, 170),
+            panel_text: Color::RGB(255, 255, 255),
+            panel_muted_text: Color::RGB(255, 255, 0),
+            birth: Color::RGB(0, 255, 0),
+            age: vec![
+                Color::RGB(255, 255, 255),
+                Color::RGB(255, 255, 0),
+                Color::RGB(255, 0, 255),
+            ],
+            heat: vec![Color::RGB(0, 128, 255), Color::RGB(255, 255, 0)],
+            trail: vec![Color::RGB(0, 255, 255), Color::RGB(0, 0, 255)],
+        }
+    }
+
+    /// Built from the Okabe-Ito palette, which stays distinguishable with the common forms of
+    /// colour blindness. Hover and birth colours never rely on red against green.
+    pub fn colour_blind() -> Theme {
+        Theme {
+            background: Color::RGB(20, 20, 24),
+            cell: Color::RGB(240, 240, 240),
+            hover_alive: Color::RGB(213, 94, 0),
+            hover_dead: Color::RGB(86, 180, 233),
+            text: Color::RGB(240, 240, 240),
+            paused_text: Color::RGB(230, 159, 0),
+            stamp: Color::RGB(86, 180, 233),
+            grid_line: Color::RGBA(240, 240, 240, 28),
+            grid_major: Color::RGBA(240, 240, 240, 64),
+            axis: Color::RGBA(86, 180, 233, 160),
+            viewport: Color::RGB(230, 159, 0),
+            panel: Color::RGB(32, 32, 40),
+            panel_selected: Color::RGB(0, 114, 178),
+            panel_text: Color::RGB(255, 255, 255),
+            panel_muted_text: Color::RGB(170, 170, 170),
+            birth: Color::RGB(240, 228, 66),
+            age: vec![
+                Color::RGB(240, 240, 240),
+                Color::RGB(240, 228, 66),
+                Color::RGB(230, 159, 0),
+                Color::RGB(213, 94, 0),
+            ],
+            heat: vec![
+                Color::RGB(0, 114, 178),
+                Color::RGB(204, 121, 167),
+                Color::RGB(230, 159, 0),
+            ],
+            trail: vec![
+                Color::RGB(86, 180, 233),
+                Color::RGB(0, 114, 178),
+                Color::RGB(0, 60, 100),
+            ],
+        }
+    }
+
+    pub fn named(name: &str) -> Option<Theme> {
+        match name {
+            "dark" => Some(Theme::dark()),
+            "light" => Some(Theme::light()),
+            "high-contrast" => Some(Theme::high_contrast()),
+            "colour-blind" | "color-blind" => Some(Theme::colour_blind()),
+            _ => None,
+        }
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::dark()
+    }
+}
+
+/// Parses `#rrggbb` or `#rrggbbaa`.
+pub fn parse_hex_color(value: &str) -> Option<Color> {
+    let hex = value.trim().strip_prefix('#')?;
+    if !matches!(hex.len(), 6 | 8) || !hex.is_ascii() {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    let alpha = if hex.len() == 8 { channel(6)? } else { 255 };
+    Some(Color::RGBA(channel(0)?, channel(2)?, channel(4)?, alpha))
+}
+
+fn lerp(a: Color, b: Color, t: f32) -> Color {
+    let channel = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t).round() as u8;
+    Color::RGBA(
+        channel(a.r, b.r),
+        channel(a.g, b.g),
+        channel(a.b, b.b),
+        channel(a.a, b.a),
+    )
+}
+
+/// Samples evenly spaced colour stops at `t` between 0 and 1, or returns `fallback` if there
+/// are no stops.
+pub fn gradient(stops: &[Color], t: f32, fallback: Color) -> Color {
+    match stops {
+        [] => fallback,
+        [only] => *only,
+        _ => {
+            let scaled = t.clamp(0.0, 1.0) * (stops.len() - 1) as f32;
+            let index = (scaled.floor() as usize).min(stops.len() - 2);
+            lerp(stops[index], stops[index + 1], scaled - index as f32)
+        }
+    }
+}
@@ -0,0 +1,326 @@
+use crate::brush::line;
+use rust_sdl_gol::gol::{GOL, Grid, Vec2Isize};
+use std::collections::HashSet;
+
+/// Largest region flood fill will fill. Anything bigger is taken to be open to the rest of the
+/// plane.
+pub const MAX_FILL_CELLS: usize = 1 << 16;
+
+/// What the left and right mouse buttons draw and erase with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Tool {
+    #[default]
+    Brush,
+    Line,
+    Rect {
+        filled: bool,
+    },
+    Ellipse {
+        filled: bool,
+    },
+    /// Fills the region of dead cells under the cursor, or clears the region of live ones.
+    Fill,
+    /// Marks a rectangle for other commands to work on, such as filling it with a soup.
+    Select,
+}
+
+impl Tool {
+    pub fn name(self) -> &'static str {
+        match self {
+            Tool::Brush => "brush",
+            Tool::Line => "line",
+            Tool::Rect { filled: false } => "rectangle",
+            Tool::Rect { filled: true } => "filled rectangle",
+            Tool::Ellipse { filled: false } => "ellipse",
+            Tool::Ellipse { filled: true } => "filled ellipse",
+            Tool::Fill => "fill",
+            Tool::Select => "select",
+        }
+    }
+
+    /// Whether the tool drags out a shape between where the mouse was pressed and where it's
+    /// released.
+    pub fn is_shape(self) -> bool {
+        matches!(
+            self,
+            Tool::Line | Tool::Rect { .. } | Tool::Ellipse { .. } | Tool::Select
+        )
+    }
+
+    /// The cells of the shape dragged out from `start` to `end`. Empty for tools that don't draw
+    /// shapes.
+    pub fn shape(self, start: Vec2Isize, end: Vec2Isize) -> Vec<Vec2Isize> {
+        match self {
+            Tool::Line => line(start, end),
+            Tool::Rect { filled } => rect(start, end, filled),
+            Tool::Select => rect(start, end, false),
+            Tool::Ellipse { filled } => ellipse(start, end, filled),
+            Tool::Brush | Tool::Fill => Vec::new(),
+        }
+    }
+}
+
+/// Inclusive `(min, max)` corners of the box with opposite corners `a` and `b`.
+pub fn bounds(a: Vec2Isize, b: Vec2Isize) -> (Vec2Isize, Vec2Isize) {
+    (
+        Vec2Isize::new(a.x.min(b.x), a.y.min(b.y)),
+        Vec2Isize::new(a.x.max(b.x), a.y.max(b.y)),
+    )
+}
+
+fn rect(a: Vec2Isize, b: Vec2Isize, filled: bool) -> Vec<Vec2Isize> {
+    let (min, max) = bounds(a, b);
+    (min.y..=max.y)
+        .flat_map(|y| (min.x..=max.x).map(move |x| Vec2Isize::new(x, y)))
+        .filter(|cell| {
+            filled || cell.x == min.x || cell.x == max.x || cell.y == min.y || cell.y == max.y
+        })
+        .collect()
+}
+
+/// The ellipse inscribed in the box with opposite corners `a` and `b`. The outline is the
+/// filled ellipse's cells that have a side open to the outside.
+fn ellipse(a: Vec2Isize, b: Vec2Isize, filled: bool) -> Vec<Vec2Isize> {
+    let (min, max) = bounds(a, b);
+    let radius_x = (max.x - min.x + 1) as f64 / 2.0;
+    let radius_y = (max.y - min.y + 1) as f64 / 2.0;
+    let inside = |x: isize, y: isize| {
+        // Measured from cell centres, so the ellipse touches every side of the box.
+        let dx = (x - min.x) as f64 + 0.5 - radius_x;
+        let dy = (y - min.y) as f64 + 0.5 - radius_y;
+        (dx / radius_x).powi(2) + (dy / radius_y).powi(2) <= 1.0
+    };
+    rect(min, max, true)
+        .into_iter()
+        .filter(|cell| inside(cell.x, cell.y))
+        .filter(|cell| {
+            filled
+                || !inside(cell.x - 1, cell.y)
+                || !inside(cell.x + 1, cell.y)
+                || !inside(cell.x, cell.y - 1)
+                || !inside(cell.x, cell.y + 1)
+        })
+        .collect()
+}
+
+/// The cells sharing `start`'s state that can be reached from it through their sides, or
+/// `None` if there are more than `MAX_FILL_CELLS` of them.
+pub fn flood(grid: &Grid, start: Vec2Isize) -> Option<Vec<Vec2Isize>> {
+    let state = grid.get_cell(start.x, start.y);
+    let mut seen = HashSet::from([start]);
+    let mut stack = vec![start];
+    let mut region = Vec::new();
+    while let Some(cell) = stack.pop() {
+        region.push(cell);
+        if region.len() > MAX_FILL_CELLS {
+            return None;
+        }
+        for (dx, dy) in [(1, 0), (-1, 0), (0, 1), (0, -1)] {
+            let next = Vec2Isize::new(cell.x + dx, cell.y + dy);
+            if grid.get_cell(next.x, next.y) == state && seen.insert(next) {
+                stack.push(next);
+            }
+        }
+    }
+    Some(region)
+}
+
+/// The selected tool and the shape being dragged out with it.
+pub struct Tools {
+    pub tool: Tool,
+    /// Inclusive `(min, max)` corners of the rectangle marked with the select tool.
+    pub selection: Option<(Vec2Isize, Vec2Isize)>,
+    /// Where the shape being dragged started, and whether it draws (left button) or erases.
+    drag: Option<(Vec2Isize, bool)>,
+    /// Cells the last preview covered, kept so flood fill needn't rerun every frame.
+    preview: Vec<Vec2Isize>,
+    /// Cursor cell and grid version the fill preview was found for.
+    fill_key: Option<(Vec2Isize, u64)>,
+}
+
+impl Tools {
+    pub fn new() -> Self {
+        Tools {
+            tool: Tool::default(),
+            selection: None,
+            drag: None,
+            preview: Vec::new(),
+            fill_key: None,
+        }
+    }
+
+    /// Switches to `tool`. Picking the rectangle or ellipse while it's already selected toggles
+    /// whether it's filled.
+    pub fn select(&mut self, tool: Tool) {
+        self.tool = match (self.tool, tool) {
+            (Tool::Rect { filled }, Tool::Rect { .. }) => Tool::Rect { filled: !filled },
+            (Tool::Ellipse { filled }, Tool::Ellipse { .. }) => Tool::Ellipse { filled: !filled },
+            _ => tool,
+        };
+        self.drag = None;
+        self.fill_key = None;
+    }
+
+    pub fn dragging(&self) -> bool {
+        self.drag.is_some()
+    }
+
+    pub fn start_drag(&mut self, cell: Vec2Isize, draw: bool) {
+        self.drag = Some((cell, draw));
+    }
+
+    /// Drops the shape being dragged without drawing it.
+    pub fn cancel(&mut self) {
+        self.drag = None;
+    }
+
+    /// Draws the dragged shape, ending at `cell`, into `grid`, or selects the dragged rectangle.
+    pub fn end_drag(&mut self, gol: &mut GOL, cell: Vec2Isize) {
+        if let Some((start, draw)) = self.drag.take() {
+            if self.tool == Tool::Select {
+                self.selection = Some(bounds(start, cell));
+                return;
+            }
+            for cell in self.tool.shape(start, cell) {
+                gol.set_cell(cell.x, cell.y, draw);
+            }
+        }
+    }
+
+    /// Fills the region under `cell` with live cells if `draw`, otherwise clears it. Regions of
+    /// the other state are left alone, as are regions too big to be enclosed.
+    pub fn fill(&self, gol: &mut GOL, cell: Vec2Isize, draw: bool) {
+        if gol.grid.get_cell(cell.x, cell.y) == draw {
+            return;
+        }
+        if let Some(region) = flood(&gol.grid, cell) {
+            for cell in region {
+                gol.set_cell(cell.x, cell.y, draw);
+            }
+        }
+    }
+
+    /// Cells the tool would change with the mouse at `cell`, and whether they'd be drawn or
+    /// erased: the shape being dragged, or the region flood fill would fill or clear.
+    ///
+    /// There's no fill preview while the simulation is `running`, as the region would have to
+    /// be flooded again for every generation.
+    pub fn preview(
+        &mut self,
+        grid: &Grid,
+        cell: Vec2Isize,
+        running: bool,
+    ) -> Option<(&[Vec2Isize], bool)> {
+        if let Some((start, draw)) = self.drag {
+            self.preview = self.tool.shape(start, cell);
+            return Some((&self.preview, draw));
+        }
+        if self.tool != Tool::Fill || running {
+            return None;
+        }
+        let key = (cell, grid.version());
+        if self.fill_key != Some(key) {
+            self.fill_key = Some(key);
+            self.preview = flood(grid, cell).unwrap_or_default();
+        }
+        Some((&self.preview, !grid.get_cell(cell.x, cell.y)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cell(x: isize, y: isize) -> Vec2Isize {
+        Vec2Isize::new(x, y)
+    }
+
+    fn set(cells: Vec<Vec2Isize>) -> HashSet<Vec2Isize> {
+        cells.into_iter().collect()
+    }
+
+    #[test]
+    fn tiny_ellipses_fill_their_box() {
+        assert_eq!(ellipse(cell(0, 0), cell(0, 0), false), vec![cell(0, 0)]);
+        assert_eq!(set(ellipse(cell(0, 0), cell(1, 1), false)).len(), 4);
+        assert_eq!(set(ellipse(cell(0, 0), cell(2, 0), false)).len(), 3);
+    }
+
+    #[test]
+    fn odd_ellipses_are_symmetric_and_touch_every_side() {
+        let outline = set(ellipse(cell(0, 0), cell(6, 4), false));
+        for c in &outline {
+            assert!(outline.contains(&cell(6 - c.x, c.y)));
+            assert!(outline.contains(&cell(c.x, 4 - c.y)));
+        }
+        for side in [cell(3, 0), cell(3, 4), cell(0, 2), cell(6, 2)] {
+            assert!(outline.contains(&side), "{:?}", side);
+        }
+        assert!(!outline.contains(&cell(3, 2)));
+        assert!(!outline.contains(&cell(0, 0)));
+    }
+
+    #[test]
+    fn hollow_shapes_are_the_edge_of_filled_ones() {
+        let (a, b) = (cell(5, 4), cell(-2, -1));
+        let (hollow, filled) = (set(rect(a, b, false)), set(rect(a, b, true)));
+        assert_eq!(filled.len(), 8 * 6);
+        assert_eq!(hollow.len(), 2 * 8 + 2 * 4);
+        assert!(hollow.is_subset(&filled));
+
+        let (hollow, filled) = (set(ellipse(a, b, false)), set(ellipse(a, b, true)));
+        assert!(hollow.is_subset(&filled));
+        assert!(hollow.len() < filled.len());
+        assert!(filled.contains(&cell(1, 1)) && !hollow.contains(&cell(1, 1)));
+    }
+
+    #[test]
+    fn flood_fills_inside_a_closed_shape() {
+        let mut gol = GOL::new(Grid::new());
+        for c in rect(cell(-3, -3), cell(3, 2), false) {
+            gol.set_cell(c.x, c.y, true);
+        }
+        let region = set(flood(&gol.grid, cell(0, 0)).unwrap());
+        assert_eq!(region, set(rect(cell(-2, -2), cell(2, 1), true)));
+
+        Tools::new().fill(&mut gol, cell(0, 0), true);
+        assert_eq!(gol.grid.population(), 7 * 6);
+        // Filling again does nothing, and the filled box now clears as a single region.
+        Tools::new().fill(&mut gol, cell(0, 0), true);
+        assert_eq!(gol.grid.population(), 7 * 6);
+        Tools::new().fill(&mut gol, cell(-3, -3), false);
+        assert!(gol.grid.is_empty());
+    }
+
+    #[test]
+    fn flood_gives_up_on_open_regions() {
+        let mut gol = GOL::new(Grid::new());
+        // A box with a gap in its side leaks into the rest of the plane.
+        for c in rect(cell(0, 0), cell(10, 10), false) {
+            gol.set_cell(c.x, c.y, c != cell(10, 5));
+        }
+        let before = gol.grid.clone();
+        assert_eq!(flood(&gol.grid, cell(5, 5)), None);
+        Tools::new().fill(&mut gol, cell(5, 5), true);
+        assert_eq!(gol.grid, before);
+    }
+
+    #[test]
+    fn flood_stops_just_past_the_limit() {
+        // A corridor one cell wide and `length` cells long, starting at the origin.
+        let corridor = |length: isize| {
+            let mut grid = Grid::new();
+            for x in -1..=length {
+                grid.set_cell(x, -1, true);
+                grid.set_cell(x, 1, true);
+            }
+            grid.set_cell(-1, 0, true);
+            grid.set_cell(length, 0, true);
+            grid
+        };
+        let longest = MAX_FILL_CELLS as isize;
+        let region = flood(&corridor(longest), cell(0, 0));
+        assert_eq!(region.map(|region| region.len()), Some(MAX_FILL_CELLS));
+        assert_eq!(flood(&corridor(longest + 1), cell(0, 0)), None);
+    }
+}
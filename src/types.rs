@@ -1,51 +1,53 @@
-use crate::gol::GOL;
-use sdl3::render::{Canvas, TextureCreator};
-use sdl3::ttf::Font;
-use sdl3::video::{Window, WindowContext};
+use crate::bookmarks::{BookmarkDialog, View};
+use crate::browser::PatternBrowser;
+use crate::brush::Brush;
+use crate::cell_layer::CellLayer;
+use crate::coloring::ColorMode;
+use crate::config::GridLines;
+use crate::density::DensityPyramid;
+use crate::follow::FollowTarget;
+use crate::goto::GotoDialog;
+use crate::random_fill::RandomFillDialog;
+use crate::minimap::Minimap;
+use crate::pacing::Pacing;
+use crate::text::TextRenderer;
+use crate::theme::Theme;
+use crate::tools::Tool;
+use rust_sdl_gol::camera::ViewState;
+use rust_sdl_gol::gol::{Grid, Vec2Isize, GOL};
+use sdl3::render::Canvas;
+use sdl3::video::Window;
+use std::collections::BTreeMap;
 use std::time::Duration;
 
-#[derive(Debug, Copy, Clone, PartialEq, Default)]
-pub struct Vector2 {
-    pub x: f32,
-    pub y: f32,
-}
-
-impl Vector2 {
-    pub fn new(x: f32, y: f32) -> Self {
-        Vector2 { x, y }
-    }
-}
-
-#[derive(Debug, Copy, Clone, PartialEq)]
-pub struct ViewState {
-    pub camera_pos: Vector2,
-    pub mouse_pos: Vector2,
-    pub zoom: f32,
-}
-
-impl Default for ViewState {
-    fn default() -> Self {
-        ViewState {
-            camera_pos: Default::default(),
-            mouse_pos: Default::default(),
-            zoom: 4.0,
-        }
-    }
-}
-
-pub struct RenderCtx<'a> {
-    pub gol: GOL,
+pub struct RenderCtx<'a, 'tc> {
+    pub gol: &'a GOL,
     pub frame_time: Duration,
     pub viewstate: ViewState,
-    pub speed: usize,
+    pub pacing: Pacing,
+    /// Generations per second actually reached lately.
+    pub achieved_gps: f32,
     pub canvas: &'a mut Canvas<Window>,
-    pub texture_creator: &'a mut TextureCreator<WindowContext>,
-    pub font: &'a Font<'a>,
+    pub text: &'a mut TextRenderer<'tc>,
+    pub cells: &'a mut CellLayer<'tc>,
+    /// Block counts of the grid, shared by the cell layer and the minimap.
+    pub density: &'a mut DensityPyramid,
+    pub browser: &'a PatternBrowser,
+    pub goto: &'a GotoDialog,
+    pub random_fill: &'a RandomFillDialog,
+    pub bookmark_dialog: &'a BookmarkDialog,
+    pub bookmarks: &'a BTreeMap<String, View>,
+    pub theme: &'a Theme,
+    pub color_mode: ColorMode,
+    pub grid_lines: &'a GridLines,
+    pub minimap: &'a mut Minimap,
+    pub brush: &'a Brush,
+    pub tool: Tool,
+    /// Inclusive corners of the rectangle marked with the select tool.
+    pub selection: Option<(Vec2Isize, Vec2Isize)>,
+    /// Cells the tool would change, and whether it would draw or erase them.
+    pub preview: Option<(&'a [Vec2Isize], bool)>,
+    pub follow: Option<FollowTarget>,
+    /// Pattern waiting to be stamped, with the cell its origin would land on.
+    pub stamp: Option<(&'a Grid, Vec2Isize)>,
 }
-
-#[derive(Debug, Clone)]
-pub struct UpdateResult {
-    pub next_grid: crate::gol::Grid,
-    pub compute_time: Duration,
-}
-
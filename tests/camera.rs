@@ -0,0 +1,122 @@
+use rust_sdl_gol::camera::{MAX_ZOOM, MIN_ZOOM, Vector2, ViewState};
+use rust_sdl_gol::gol::Vec2Isize;
+
+fn assert_close(actual: Vector2, expected: Vector2) {
+    let close = (actual.x - expected.x).abs() < 1e-3 && (actual.y - expected.y).abs() < 1e-3;
+    assert!(close, "{:?} is not close to {:?}", actual, expected);
+}
+
+fn view(camera_x: f32, camera_y: f32, zoom: f32) -> ViewState {
+    ViewState {
+        camera_pos: Vector2::new(camera_x, camera_y),
+        zoom,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn screen_x_is_the_column_and_screen_y_the_row() {
+    let view = view(0.0, 0.0, 10.0);
+    assert_eq!(
+        view.screen_to_cell(Vector2::new(35.0, 12.0)),
+        Vec2Isize::new(3, 1)
+    );
+    assert_eq!(
+        view.cell_to_screen(Vec2Isize::new(3, 1)),
+        Vector2::new(30.0, 10.0)
+    );
+}
+
+#[test]
+fn negative_cells_round_towards_negative_infinity() {
+    let view = view(100.0, 100.0, 8.0);
+    // Just left of and above the origin cell's corner.
+    assert_eq!(
+        view.screen_to_cell(Vector2::new(99.0, 99.0)),
+        Vec2Isize::new(-1, -1)
+    );
+    assert_eq!(
+        view.screen_to_cell(Vector2::new(92.0, 100.0)),
+        Vec2Isize::new(-1, 0)
+    );
+    assert_eq!(
+        view.screen_to_cell(Vector2::new(91.9, 100.0)),
+        Vec2Isize::new(-2, 0)
+    );
+    assert_eq!(
+        view.screen_to_cell(Vector2::new(0.0, 0.0)),
+        Vec2Isize::new(-13, -13)
+    );
+}
+
+#[test]
+fn every_cell_maps_back_to_itself() {
+    for zoom in [MIN_ZOOM, 0.37, 1.0, 3.0, 64.0, MAX_ZOOM] {
+        let view = view(-123.5, 456.25, zoom);
+        for cell in [
+            Vec2Isize::new(0, 0),
+            Vec2Isize::new(-1, -1),
+            Vec2Isize::new(-7, 12),
+            Vec2Isize::new(250, -3_000),
+        ] {
+            let corner = view.cell_to_screen(cell);
+            // Sample inside the cell, away from edges that rounding could push either way.
+            let inside = Vector2::new(corner.x + zoom / 2.0, corner.y + zoom / 2.0);
+            assert_eq!(view.screen_to_cell(inside), cell, "zoom {}", zoom);
+        }
+    }
+}
+
+#[test]
+fn far_out_cells_stay_distinct_at_extreme_zoom() {
+    let far = 1_000_000;
+    let view = view(-(far as f32) * MAX_ZOOM, 0.0, MAX_ZOOM);
+    assert_eq!(
+        view.screen_to_cell(Vector2::new(0.0, 0.0)),
+        Vec2Isize::new(far, 0)
+    );
+    assert_eq!(
+        view.screen_to_cell(Vector2::new(9_999.0, 0.0)),
+        Vec2Isize::new(far, 0)
+    );
+    assert_eq!(
+        view.screen_to_cell(Vector2::new(10_000.0, 0.0)),
+        Vec2Isize::new(far + 1, 0)
+    );
+}
+
+#[test]
+fn zoomed_far_out_one_pixel_covers_many_cells() {
+    let view = view(0.0, 0.0, MIN_ZOOM);
+    let point = Vector2::new(1.00005, -1.00005);
+    assert_eq!(view.screen_to_cell(point), Vec2Isize::new(10_000, -10_001));
+    let point = Vector2::new(-0.50005, 0.50005);
+    assert_eq!(view.screen_to_cell(point), Vec2Isize::new(-5_001, 5_000));
+}
+
+#[test]
+fn visible_cells_cover_partly_shown_edges() {
+    let view = view(-5.0, 3.0, 10.0);
+    let (min, max) = view.visible_cells((100, 50));
+    assert_eq!(min, Vec2Isize::new(0, -1));
+    assert_eq!(max, Vec2Isize::new(10, 4));
+}
+
+#[test]
+fn centring_on_a_point_puts_it_mid_window() {
+    let mut view = view(0.0, 0.0, 4.0);
+    view.center_on(-20.5, 7.0, (800, 600));
+    assert_close(view.center((800, 600)), Vector2::new(-20.5, 7.0));
+    assert_eq!(
+        view.screen_to_cell(Vector2::new(400.0, 300.0)),
+        Vec2Isize::new(-21, 7)
+    );
+}
+
+#[test]
+fn fit_frames_wide_patterns_by_their_width() {
+    let mut view = ViewState::default();
+    view.fit((Vec2Isize::new(-10, 0), Vec2Isize::new(89, 9)), (800, 600));
+    assert!((view.zoom - 7.2).abs() < 1e-4);
+    assert_close(view.center((800, 600)), Vector2::new(40.0, 5.0));
+}
@@ -0,0 +1,125 @@
+use rust_sdl_gol::formats::{
+    MAX_RUN_LENGTH, parse_plaintext, parse_rle, write_plaintext, write_rle,
+};
+use rust_sdl_gol::gol::{Grid, Vec2Isize};
+use rust_sdl_gol::rule::Rule;
+
+fn grid(cells: &[(isize, isize)]) -> Grid {
+    cells.iter().map(|&(x, y)| Vec2Isize::new(x, y)).collect()
+}
+
+fn glider() -> Grid {
+    grid(&[(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)])
+}
+
+#[test]
+fn rle_reads_a_glider() {
+    let text = "#N Glider\nx = 3, y = 3, rule = B3/S23\nbob$2bo$3o!\n";
+    let pattern = parse_rle(text).unwrap();
+    assert_eq!(pattern.name.as_deref(), Some("Glider"));
+    assert_eq!(pattern.rule.as_deref(), Some("B3/S23"));
+    assert_eq!(pattern.grid, glider());
+}
+
+#[test]
+fn rle_runs_span_lines_and_skip_rows() {
+    let pattern = parse_rle("x = 4, y = 3\n2o\n2bo2$o!").unwrap();
+    assert_eq!(pattern.grid, grid(&[(0, 0), (1, 0), (4, 0), (0, 2)]));
+}
+
+#[test]
+fn rle_stops_at_the_end_marker() {
+    let pattern = parse_rle("x = 1, y = 1\no!\n3o!").unwrap();
+    assert_eq!(pattern.grid, grid(&[(0, 0)]));
+}
+
+#[test]
+fn rle_rejects_overflowing_runs() {
+    let error = parse_rle("x = 1, y = 1\n99999999999999999999999o!").unwrap_err();
+    assert_eq!(error.line, 2);
+}
+
+#[test]
+fn rle_rejects_runs_that_are_too_long() {
+    assert!(parse_rle("x = 1, y = 1\n2000000000o!").is_err());
+    assert!(parse_rle(&format!("x = 1, y = 1\n{}b!", MAX_RUN_LENGTH + 1)).is_err());
+    let longest = parse_rle(&format!("x = 1, y = 1\n{}bo!", MAX_RUN_LENGTH)).unwrap();
+    assert_eq!(longest.grid, grid(&[(MAX_RUN_LENGTH, 0)]));
+}
+
+#[test]
+fn rle_rejects_bad_headers_and_characters() {
+    assert_eq!(parse_rle("x = 3, y\n3o!").unwrap_err().line, 1);
+    assert_eq!(parse_rle("x = a, y = 1\n3o!").unwrap_err().line, 1);
+    assert_eq!(parse_rle("x = 3, y = 1\n\n3q!").unwrap_err().line, 3);
+}
+
+#[test]
+fn plaintext_reads_a_glider() {
+    let pattern = parse_plaintext("!Name: Glider\n.O\n..O\nOOO\n").unwrap();
+    assert_eq!(pattern.name.as_deref(), Some("Glider"));
+    assert_eq!(pattern.grid, glider());
+}
+
+#[test]
+fn plaintext_rejects_unknown_characters() {
+    assert_eq!(parse_plaintext(".O\nOx\n").unwrap_err().line, 2);
+}
+
+#[test]
+fn rle_round_trips() {
+    let rule = Rule::default();
+    let pattern = grid(&[(0, 0), (5, 0), (2, 3), (3, 3), (4, 3), (0, 7)]);
+    let text = write_rle(&pattern, &rule);
+    assert_eq!(parse_rle(&text).unwrap().grid, pattern);
+}
+
+#[test]
+fn rle_round_trips_long_rows_split_across_lines() {
+    let pattern: Grid = (0..200)
+        .filter(|x| x % 3 != 1)
+        .map(|x| Vec2Isize::new(x, x % 2))
+        .collect();
+    let text = write_rle(&pattern, &Rule::default());
+    assert!(text.lines().all(|line| line.len() <= 70));
+    assert_eq!(parse_rle(&text).unwrap().grid, pattern);
+}
+
+#[test]
+fn patterns_are_written_from_their_top_left_corner() {
+    let moved = grid(&[(-9, -4), (-8, -3), (-10, -2), (-9, -2), (-8, -2)]);
+    assert_eq!(
+        parse_rle(&write_rle(&moved, &Rule::default()))
+            .unwrap()
+            .grid,
+        glider()
+    );
+    assert_eq!(
+        parse_plaintext(&write_plaintext(&moved)).unwrap().grid,
+        glider()
+    );
+}
+
+#[test]
+fn plaintext_round_trips() {
+    let pattern = grid(&[(0, 0), (5, 0), (2, 3), (3, 3), (4, 3), (0, 7)]);
+    let text = write_plaintext(&pattern);
+    assert_eq!(parse_plaintext(&text).unwrap().grid, pattern);
+}
+
+#[test]
+fn empty_grids_round_trip() {
+    let empty = Grid::new();
+    assert!(
+        parse_rle(&write_rle(&empty, &Rule::default()))
+            .unwrap()
+            .grid
+            .is_empty()
+    );
+    assert!(
+        parse_plaintext(&write_plaintext(&empty))
+            .unwrap()
+            .grid
+            .is_empty()
+    );
+}
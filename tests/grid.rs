@@ -0,0 +1,175 @@
+use rust_sdl_gol::gol::{Engine, GOL, Grid, Vec2Isize};
+use rust_sdl_gol::rule::Rule;
+
+fn grid(cells: &[(isize, isize)]) -> Grid {
+    cells.iter().map(|&(x, y)| Vec2Isize::new(x, y)).collect()
+}
+
+fn sorted(cells: impl Iterator<Item = Vec2Isize>) -> Vec<(isize, isize)> {
+    let mut cells: Vec<_> = cells.map(|cell| (cell.x, cell.y)).collect();
+    cells.sort();
+    cells
+}
+
+/// Cells either side of the tile boundaries around the origin, including the far corners of
+/// the tiles that meet there.
+const EDGES: [(isize, isize); 8] = [
+    (-1, -1),
+    (0, 0),
+    (-1, 0),
+    (0, -1),
+    (63, 63),
+    (64, 64),
+    (-64, -64),
+    (-65, -65),
+];
+
+#[test]
+fn cells_either_side_of_tile_edges_are_independent() {
+    for (x, y) in EDGES {
+        let mut grid = Grid::new();
+        grid.set_cell(x, y, true);
+        for (other_x, other_y) in EDGES {
+            assert_eq!(
+                grid.get_cell(other_x, other_y),
+                (other_x, other_y) == (x, y)
+            );
+        }
+        assert_eq!(sorted(grid.iter()), vec![(x, y)]);
+    }
+}
+
+#[test]
+fn negative_coordinates_far_from_the_origin() {
+    let cells = [(-1_000_000, -3), (-3, -1_000_000), (isize::MIN, isize::MIN)];
+    let grid = grid(&cells);
+    for (x, y) in cells {
+        assert!(grid.get_cell(x, y));
+        assert!(!grid.get_cell(x + 1, y));
+        assert!(!grid.get_cell(x, y + 1));
+    }
+    assert_eq!(grid.population(), 3);
+}
+
+#[test]
+fn population_counts_only_changes() {
+    let mut grid = Grid::new();
+    grid.set_cell(-5, 7, true);
+    grid.set_cell(-5, 7, true);
+    grid.set_cell(70, -70, true);
+    assert_eq!(grid.population(), 2);
+    grid.set_cell(0, 0, false);
+    assert_eq!(grid.population(), 2);
+    grid.set_cell(-5, 7, false);
+    grid.set_cell(-5, 7, false);
+    assert_eq!(grid.population(), 1);
+    grid.set_cell(70, -70, false);
+    assert_eq!(grid.population(), 0);
+    assert!(grid.is_empty());
+    assert_eq!(grid.bounds(), None);
+}
+
+#[test]
+fn emptied_grids_equal_new_ones() {
+    let mut grid = grid(&[(-1, -1), (0, 0), (64, -64)]);
+    for (x, y) in [(-1, -1), (0, 0), (64, -64)] {
+        grid.set_cell(x, y, false);
+    }
+    assert_eq!(grid, Grid::new());
+    assert_eq!(grid.iter().count(), 0);
+}
+
+#[test]
+fn versions_change_only_with_the_cells() {
+    let mut grid = Grid::new();
+    let empty = grid.version();
+    grid.set_cell(3, 3, false);
+    assert_eq!(grid.version(), empty);
+    grid.set_cell(3, 3, true);
+    let one = grid.version();
+    assert_ne!(one, empty);
+    assert_eq!(grid.clone().version(), one);
+    grid.set_cell(3, 3, true);
+    assert_eq!(grid.version(), one);
+}
+
+#[test]
+fn cells_in_spans_tiles_and_stays_inside_the_rectangle() {
+    let grid = grid(&[
+        (-65, -1),
+        (-64, 0),
+        (-1, -1),
+        (0, 0),
+        (63, 64),
+        (64, 64),
+        (200, 200),
+    ]);
+    let (min, max) = (Vec2Isize::new(-64, -1), Vec2Isize::new(63, 64));
+    let expected = vec![(-64, 0), (-1, -1), (0, 0), (63, 64)];
+    assert_eq!(sorted(grid.cells_in(min, max)), expected);
+    assert_eq!(grid.population_in(min, max), 4);
+}
+
+#[test]
+fn cells_in_agrees_for_small_and_large_rectangles() {
+    // Small rectangles probe tile positions, large ones walk the stored tiles.
+    let grid: Grid = (-300..300)
+        .step_by(7)
+        .map(|i| Vec2Isize::new(i, -i / 2))
+        .collect();
+    for (min, max) in [
+        ((-10, -10), (10, 10)),
+        ((-70, -5), (70, 40)),
+        ((-1000, -1000), (1000, 1000)),
+    ] {
+        let (min, max) = (Vec2Isize::new(min.0, min.1), Vec2Isize::new(max.0, max.1));
+        let expected = sorted(
+            grid.iter()
+                .filter(|c| (min.x..=max.x).contains(&c.x) && (min.y..=max.y).contains(&c.y)),
+        );
+        assert_eq!(sorted(grid.cells_in(min, max)), expected);
+    }
+}
+
+#[test]
+fn cells_in_an_inverted_rectangle_is_empty() {
+    let grid = grid(&[(0, 0)]);
+    assert_eq!(
+        grid.cells_in(Vec2Isize::new(1, 0), Vec2Isize::new(0, 0))
+            .count(),
+        0
+    );
+}
+
+#[test]
+fn bounds_cover_negative_cells() {
+    let grid = grid(&[(-70, 5), (3, -130), (10, 10)]);
+    assert_eq!(
+        grid.bounds(),
+        Some((Vec2Isize::new(-70, -130), Vec2Isize::new(10, 10)))
+    );
+}
+
+#[test]
+fn a_glider_crosses_tile_edges_at_negative_coordinates() {
+    // Heading up and to the left from just inside the tile below and right of the origin.
+    let glider = [(0, 0), (1, 0), (2, 0), (0, 1), (1, 2)];
+    let start: Grid = glider
+        .iter()
+        .map(|&(x, y)| Vec2Isize::new(x + 2, y + 2))
+        .collect();
+    let mut gol = GOL::new(start);
+    gol.engine = Engine::Serial;
+    gol.rule = Rule::default();
+    for period in 1..=40 {
+        for _ in 0..4 {
+            gol.step();
+        }
+        let expected: Grid = glider
+            .iter()
+            .map(|&(x, y)| Vec2Isize::new(x + 2 - period, y + 2 - period))
+            .collect();
+        assert_eq!(*gol.grid, expected, "after {} periods", period);
+        assert_eq!(gol.grid.population(), 5);
+    }
+}
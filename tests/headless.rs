@@ -0,0 +1,97 @@
+use rust_sdl_gol::gol::{Engine, Grid, Vec2Isize};
+use rust_sdl_gol::headless::{Cycle, DEFAULT_STABLE_LIMIT, parse_args, run, simulate};
+use rust_sdl_gol::rule::Rule;
+
+fn grid(cells: &[(isize, isize)]) -> Grid {
+    cells.iter().map(|&(x, y)| Vec2Isize::new(x, y)).collect()
+}
+
+fn glider() -> Grid {
+    grid(&[(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)])
+}
+
+fn args(args: &[&str]) -> Vec<String> {
+    args.iter().map(|arg| arg.to_string()).collect()
+}
+
+#[test]
+fn a_still_life_is_stable_at_once() {
+    let block = grid(&[(0, 0), (1, 0), (0, 1), (1, 1)]);
+    let outcome = simulate(block.clone(), &Rule::default(), Engine::Serial, None, true);
+    assert_eq!(outcome.generation, 1);
+    assert_eq!(outcome.grid, block);
+    assert_eq!(
+        outcome.cycle,
+        Some(Cycle {
+            period: 1,
+            start: 0,
+            offset: Vec2Isize::new(0, 0),
+        })
+    );
+}
+
+#[test]
+fn a_blinker_has_period_two() {
+    let blinker = grid(&[(0, 1), (1, 1), (2, 1)]);
+    let outcome = simulate(
+        blinker.clone(),
+        &Rule::default(),
+        Engine::Serial,
+        None,
+        true,
+    );
+    assert_eq!(outcome.generation, 2);
+    assert_eq!(outcome.grid, blinker);
+    let cycle = outcome.cycle.unwrap();
+    assert_eq!((cycle.period, cycle.start), (2, 0));
+    assert_eq!(cycle.offset, Vec2Isize::new(0, 0));
+}
+
+#[test]
+fn a_glider_repeats_shifted_diagonally() {
+    let outcome = simulate(glider(), &Rule::default(), Engine::Serial, Some(100), true);
+    let cycle = outcome.cycle.unwrap();
+    assert_eq!((cycle.period, cycle.start), (4, 0));
+    assert_eq!(cycle.offset, Vec2Isize::new(1, 1));
+}
+
+#[test]
+fn a_capped_glider_run_stops_at_the_limit() {
+    let outcome = simulate(glider(), &Rule::default(), Engine::Serial, Some(40), false);
+    assert_eq!(outcome.generation, 40);
+    assert_eq!(outcome.cycle, None);
+    let moved: Grid = glider()
+        .iter()
+        .map(|cell| Vec2Isize::new(cell.x + 10, cell.y + 10))
+        .collect();
+    assert_eq!(outcome.grid, moved);
+}
+
+#[test]
+fn until_stable_alone_is_capped() {
+    let options = parse_args(&args(&["glider.rle", "--until-stable"])).unwrap();
+    assert_eq!(options.generations, Some(DEFAULT_STABLE_LIMIT));
+    let options = parse_args(&args(&[
+        "glider.rle",
+        "--until-stable",
+        "--generations",
+        "5",
+    ]));
+    assert_eq!(options.unwrap().generations, Some(5));
+    assert!(parse_args(&args(&["glider.rle"])).is_err());
+}
+
+#[test]
+fn run_reports_the_result() {
+    let pattern = concat!(env!("CARGO_MANIFEST_DIR"), "/patterns/glider.rle");
+    let report = run(&parse_args(&args(&[pattern, "--until-stable"])).unwrap()).unwrap();
+    assert_eq!(report.rule, Rule::default());
+    assert_eq!(report.generation, 4);
+    assert_eq!(report.population, 5);
+    assert_eq!(
+        report.bounds,
+        Some((Vec2Isize::new(1, 1), Vec2Isize::new(3, 3)))
+    );
+    assert_eq!(report.cycle.map(|cycle| cycle.period), Some(4));
+    assert!(run(&parse_args(&args(&["missing.rle", "--generations", "1"])).unwrap()).is_err());
+}
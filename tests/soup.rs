@@ -0,0 +1,141 @@
+use rust_sdl_gol::gol::Grid;
+use rust_sdl_gol::soup::{Soup, Symmetry};
+
+fn soup(width: usize, height: usize, symmetry: Symmetry, seed: u64) -> Soup {
+    Soup {
+        width,
+        height,
+        density: 0.5,
+        symmetry,
+        seed,
+    }
+}
+
+/// Where each of the symmetry's transformations takes the cell `(x, y)` of a `width` by
+/// `height` soup.
+fn images(
+    symmetry: Symmetry,
+    width: isize,
+    height: isize,
+    (x, y): (isize, isize),
+) -> Vec<(isize, isize)> {
+    let (flip_x, flip_y) = (width - 1 - x, height - 1 - y);
+    match symmetry {
+        Symmetry::C1 => vec![],
+        Symmetry::C2 => vec![(flip_x, flip_y)],
+        Symmetry::C4 => vec![(flip_y, x), (flip_x, flip_y), (y, flip_x)],
+        Symmetry::D2 => vec![(flip_x, y)],
+        Symmetry::D4 => vec![(flip_x, y), (x, flip_y), (flip_x, flip_y)],
+        Symmetry::D8 => vec![
+            (flip_x, y),
+            (x, flip_y),
+            (flip_x, flip_y),
+            (y, x),
+            (flip_y, x),
+            (y, flip_x),
+            (flip_y, flip_x),
+        ],
+    }
+}
+
+fn assert_symmetric(soup: &Soup, grid: &Grid) {
+    let (width, height) = (soup.width as isize, soup.height as isize);
+    for y in 0..height {
+        for x in 0..width {
+            for (u, v) in images(soup.symmetry, width, height, (x, y)) {
+                assert_eq!(
+                    grid.get_cell(x, y),
+                    grid.get_cell(u, v),
+                    "{} soup: ({}, {}) and ({}, {}) differ",
+                    soup,
+                    x,
+                    y,
+                    u,
+                    v
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn every_symmetry_holds_for_odd_and_even_sizes() {
+    for symmetry in Symmetry::ALL {
+        let sizes: &[(usize, usize)] = if symmetry.needs_square() {
+            &[(15, 15), (16, 16)]
+        } else {
+            &[(15, 15), (16, 16), (15, 10), (16, 9)]
+        };
+        for &(width, height) in sizes {
+            for seed in 0..4 {
+                let soup = soup(width, height, symmetry, seed);
+                assert_symmetric(&soup, &soup.generate());
+            }
+        }
+    }
+}
+
+#[test]
+fn soups_stay_inside_their_rectangle() {
+    let soup = Soup {
+        density: 1.0,
+        ..soup(7, 4, Symmetry::D4, 1)
+    };
+    let grid = soup.generate();
+    assert_eq!(grid.population(), 28);
+    assert_eq!(grid.bounds().map(|(_, max)| (max.x, max.y)), Some((6, 3)));
+}
+
+#[test]
+fn the_same_seed_gives_the_same_soup() {
+    for symmetry in Symmetry::ALL {
+        let first = soup(32, 32, symmetry, 1234).generate();
+        assert_eq!(soup(32, 32, symmetry, 1234).generate(), first);
+        assert_ne!(soup(32, 32, symmetry, 1235).generate(), first);
+    }
+}
+
+#[test]
+fn density_sets_the_share_of_live_cells() {
+    let empty = Soup {
+        density: 0.0,
+        ..soup(20, 20, Symmetry::C1, 5)
+    };
+    assert!(empty.generate().is_empty());
+    let sparse = Soup {
+        density: 0.1,
+        ..soup(100, 100, Symmetry::C1, 5)
+    };
+    let population = sparse.generate().population();
+    assert!((700..1300).contains(&population), "{} cells", population);
+}
+
+#[test]
+fn soups_display_the_density_as_typed() {
+    let soup = Soup {
+        density: 7.0 / 100.0,
+        ..soup(16, 8, Symmetry::D2, 42)
+    };
+    assert_eq!(soup.to_string(), "16x8 7% D2 42");
+    let third = Soup {
+        density: 100.0 / 3.0 / 100.0,
+        ..soup
+    };
+    let shown = third.to_string();
+    let percent: f64 = shown
+        .split_whitespace()
+        .nth(1)
+        .unwrap()
+        .trim_end_matches('%')
+        .parse()
+        .unwrap();
+    assert_eq!(percent / 100.0, third.density);
+}
+
+#[test]
+fn symmetries_parse_by_name() {
+    for symmetry in Symmetry::ALL {
+        assert_eq!(symmetry.name().to_lowercase().parse(), Ok(symmetry));
+    }
+    assert!("C3".parse::<Symmetry>().is_err());
+}